@@ -0,0 +1,116 @@
+/*!
+How fast the board answers questions, and how fast games move on it.
+
+    cargo bench --bench board
+*/
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hexaroni::ai::Position;
+use hexaroni::engine::{Board, Player, RuleSet};
+use hexaroni::game::{generator::Generator, moves::legal_moves};
+use hexaroni::geometry::HexCoord;
+
+fn boards() -> Vec<(&'static str, Board)> {
+    let generated = Generator {
+        radius: 8,
+        dashers: 8,
+        jumpers: 8,
+        pushers: 4,
+        bombers: 4,
+        ..Default::default()
+    };
+    vec![
+        ("test_square", Board::test_square()),
+        (
+            "generated",
+            generated.generate(0, &RuleSet::default()).expect("a board"),
+        ),
+    ]
+}
+
+/// the same boards one move into a game, as the bots look things up on them
+fn played(boards: Vec<(&'static str, Board)>) -> Vec<(String, Board)> {
+    let rules = RuleSet::default();
+    let mut played = Vec::new();
+    for (name, board) in boards {
+        let position = Position::new(&board, Player::A, 0, &rules);
+        let after = position.play(position.legal_moves().first());
+        played.push((name.to_string(), board));
+        played.push((format!("{}/after_play", name), after.board));
+    }
+    played
+}
+
+fn coords(board: &Board) -> Vec<HexCoord> {
+    (0..board.size)
+        .flat_map(|x| (0..board.size).map(move |y| HexCoord::new(x, y, board.size)))
+        .collect()
+}
+
+fn lookups(c: &mut Criterion) {
+    for (name, board) in played(boards()) {
+        let coords = coords(&board);
+        let mut group = c.benchmark_group(format!("lookups/{}", name));
+        group.bench_function("tile_at", |b| {
+            b.iter(|| coords.iter().filter(|c| board.tile_at(c).is_some()).count())
+        });
+        group.bench_function("piece_at", |b| {
+            b.iter(|| {
+                coords
+                    .iter()
+                    .filter(|c| board.piece_at(c).is_some())
+                    .count()
+            })
+        });
+        group.bench_function("contents", |b| {
+            b.iter(|| {
+                coords
+                    .iter()
+                    .filter(|c| board.contents(c).is_some())
+                    .count()
+            })
+        });
+        let objects: Vec<_> = board.objects().into_iter().cloned().collect();
+        let mut board = board.clone();
+        group.bench_function("get_as_mut", |b| {
+            b.iter(|| {
+                objects
+                    .iter()
+                    .filter(|o| board.get_as_mut(o).is_some())
+                    .count()
+            })
+        });
+        group.finish();
+    }
+}
+
+fn search(c: &mut Criterion) {
+    let rules = RuleSet::default();
+    for (name, board) in boards() {
+        let mut group = c.benchmark_group(format!("search/{}", name));
+        group.bench_function("clone", |b| b.iter(|| black_box(&board).clone()));
+        group.bench_function("legal_moves", |b| {
+            b.iter(|| {
+                board
+                    .pieces()
+                    .into_iter()
+                    .flat_map(|p| legal_moves(p, &board, &rules))
+                    .count()
+            })
+        });
+        let position = Position::new(&board, Player::A, 0, &rules);
+        let moves = position.legal_moves();
+        group.bench_function("play_every_move", |b| {
+            b.iter(|| {
+                moves
+                    .iter()
+                    .map(|m| position.play(Some(m)))
+                    .filter(|p| p.winner().is_some())
+                    .count()
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, lookups, search);
+criterion_main!(benches);
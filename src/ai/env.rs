@@ -0,0 +1,330 @@
+use crate::ai::{Bot, Position};
+use crate::engine::{Board, ObjectType, Player, RuleSet};
+use crate::game::moves::Move;
+use crate::geometry::HexCoord;
+use std::collections::HashMap;
+use std::fmt;
+
+pub const DIRECTIONS: usize = 6;
+pub const HOOKS: usize = 3;
+pub const CHANNELS: usize = 12;
+
+/**
+Which way a move bends after its first leg.
+Dashers always go `Straight`, Jumpers hook one way or the other.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    Straight,
+    Clockwise,
+    CounterClockwise,
+}
+
+/**
+The board as seen by the player to move: `CHANNELS` planes of
+`board_size` x `board_size` values, indexed by `HexCoord`.
+
+| channel | meaning                                      |
+|---------|----------------------------------------------|
+| 0       | 1 if there is a tile                         |
+| 1       | moves until the tile falls, / max lifespan   |
+| 2       | 1 if the tile falls within the heads-up time |
+| 3       | 1 if there is a wall                         |
+| 4, 5    | 1 for an own Dasher, Jumper                  |
+| 6, 7    | 1 for an opposing Dasher, Jumper             |
+| 8, 9    | 1 for an own, opposing Pusher                |
+| 10, 11  | 1 for an own, opposing Bomber                |
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub data: Vec<f32>,
+    /// `[CHANNELS, board_size, board_size]`, in the order `data` is laid out
+    pub shape: [usize; 3],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepInfo {
+    pub move_nr: usize,
+    pub to_move: Player,
+    pub winner: Option<Player>,
+    /// the game was cut off at `Env::max_moves`
+    pub truncated: bool,
+    /// the action was not legal, and the turn was passed instead
+    pub illegal: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvError {
+    /// `step` after the episode ended, without a `reset`
+    EpisodeOver,
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::EpisodeOver => write!(f, "the episode is over; reset to start a new one"),
+        }
+    }
+}
+
+impl std::error::Error for EnvError {}
+
+/**
+A gym-style environment for training agents.
+
+Actions are numbered over (piece, direction, hook): each player's pieces get a
+slot when the environment is reset (by `oid`), and action
+`(slot * DIRECTIONS + direction) * HOOKS + hook` moves that piece. A Bomber
+lighting its fuse is direction 0 with a clockwise hook. The last action
+passes the turn, as if the move timed out, and is always legal.
+
+Without an opponent, the agent plays both sides. With one, the agent is
+the starting player of `rules`, and every step also plays the opponent's reply.
+Rewards are +1 for winning, -1 for losing and 0 otherwise, for the player
+who took the step. Once a step reports that the episode is done, further
+steps fail until the next `reset`.
+*/
+pub struct Env {
+    start: Board,
+    position: Position,
+    opponent: Option<Box<dyn Bot>>,
+    /// oids of each player's pieces, by slot
+    slots: HashMap<Player, Vec<usize>>,
+    max_pieces: usize,
+    max_lifespan: usize,
+    /// the last step ended the episode
+    done: bool,
+    pub max_moves: usize,
+    /// the rules episodes are played by, from the next `reset` on
+    pub rules: RuleSet,
+}
+
+impl Observation {
+    pub fn get(&self, channel: usize, coord: &HexCoord) -> f32 {
+        let [_, height, width] = self.shape;
+        self.data[(channel * height + coord.y) * width + coord.x]
+    }
+
+    fn set(&mut self, channel: usize, coord: &HexCoord, value: f32) {
+        let [_, height, width] = self.shape;
+        self.data[(channel * height + coord.y) * width + coord.x] = value;
+    }
+}
+
+impl Env {
+    pub fn new(board: Board) -> Env {
+        let rules = RuleSet::default();
+        let slots: HashMap<Player, Vec<usize>> = [Player::A, Player::B]
+            .into_iter()
+            .map(|player| {
+                let mut oids: Vec<usize> = board
+                    .pieces()
+                    .iter()
+                    .filter(|p| p.owned_by(&player))
+                    .map(|p| p.props.oid)
+                    .collect();
+                oids.sort();
+                (player, oids)
+            })
+            .collect();
+        let max_pieces = slots.values().map(|s| s.len()).max().unwrap_or_default();
+        let max_lifespan = board
+            .tiles()
+            .iter()
+            .filter_map(|t| t.lifespan())
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        Env {
+            position: Position::new(&board, rules.starting_player, 0, &rules),
+            start: board,
+            opponent: None,
+            slots,
+            max_pieces,
+            max_lifespan,
+            done: false,
+            max_moves: 1000,
+            rules,
+        }
+    }
+
+    pub fn with_opponent(board: Board, opponent: Box<dyn Bot>) -> Env {
+        Env {
+            opponent: Some(opponent),
+            ..Env::new(board)
+        }
+    }
+
+    pub fn num_actions(&self) -> usize {
+        self.max_pieces * DIRECTIONS * HOOKS + 1
+    }
+
+    pub fn pass_action(&self) -> usize {
+        self.num_actions() - 1
+    }
+
+    pub fn observation_shape(&self) -> [usize; 3] {
+        [CHANNELS, self.start.size, self.start.size]
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    /**
+    starts a new episode from the starting board.
+    `seed` is handed to the opponent, so that episodes can be repeated.
+    */
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        let mut board = self.start.clone();
+        board.apply_rules(&self.rules);
+        self.position = Position::new(&board, self.rules.starting_player, 0, &self.rules);
+        self.done = false;
+        if let Some(opponent) = &mut self.opponent {
+            opponent.reseed(seed);
+        }
+        self.observe()
+    }
+
+    pub fn step(&mut self, action: usize) -> Result<(Observation, f32, bool, StepInfo), EnvError> {
+        if self.done {
+            return Err(EnvError::EpisodeOver);
+        }
+        let player = self.position.to_move;
+        let chosen = self
+            .legal_actions()
+            .into_iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, m)| m);
+        let illegal = chosen.is_none() && action != self.pass_action();
+        self.position.advance(chosen.as_ref());
+
+        if let Some(opponent) = &mut self.opponent {
+            if self.position.winner().is_none() {
+                let reply = opponent.choose_move(&self.position);
+                self.position.advance(reply.as_ref());
+            }
+        }
+
+        let winner = self.position.winner();
+        let reward = match winner {
+            Some(Player::God) | None => 0.0,
+            Some(w) if w == player => 1.0,
+            Some(_) => -1.0,
+        };
+        let truncated = winner.is_none() && self.position.move_nr >= self.max_moves;
+        let info = StepInfo {
+            move_nr: self.position.move_nr,
+            to_move: self.position.to_move,
+            winner,
+            truncated,
+            illegal,
+        };
+        self.done = winner.is_some() || truncated;
+        Ok((self.observe(), reward, self.done, info))
+    }
+
+    /// `true` for every action that is legal for the player to move
+    pub fn action_mask(&self) -> Vec<bool> {
+        let mut mask = vec![false; self.num_actions()];
+        for (action, _) in self.legal_actions() {
+            mask[action] = true;
+        }
+        mask[self.pass_action()] = true;
+        mask
+    }
+
+    /// the legal moves of the player to move, with their action numbers
+    pub fn legal_actions(&self) -> Vec<(usize, Move)> {
+        self.position
+            .legal_moves()
+            .into_iter()
+            .filter_map(|m| self.encode(&m).map(|a| (a, m)))
+            .collect()
+    }
+
+    pub fn encode(&self, r#move: &Move) -> Option<usize> {
+        let slot = self
+            .slots
+            .get(&r#move.object.player)?
+            .iter()
+            .position(|oid| *oid == r#move.object.props.oid)?;
+        let from = r#move.path[0];
+        let (direction, hook) = match r#move.object.otype {
+            ObjectType::Jumper => {
+                let turn = r#move.path[1];
+                let direction = (0..DIRECTIONS).find(|&d| from.get_neighbor(d, 2) == Some(turn))?;
+                let hook = if turn.get_neighbor((direction + 1) % 6, 1) == Some(*r#move.target()) {
+                    Hook::Clockwise
+                } else {
+                    Hook::CounterClockwise
+                };
+                (direction, hook)
+            }
+            // a Bomber lighting its fuse stays put; no other move uses this slot
+            ObjectType::Bomber if r#move.target() == &from => (0, Hook::Clockwise),
+            _ => {
+                let direction =
+                    (0..DIRECTIONS).find(|&d| from.get_neighbor(d, 1) == Some(r#move.path[1]))?;
+                (direction, Hook::Straight)
+            }
+        };
+        Some((slot * DIRECTIONS + direction) * HOOKS + hook as usize)
+    }
+
+    /// the (slot, direction, hook) an action stands for; `None` for passing
+    pub fn decode(&self, action: usize) -> Option<(usize, usize, Hook)> {
+        if action >= self.pass_action() {
+            return None;
+        }
+        let hook = match action % HOOKS {
+            0 => Hook::Straight,
+            1 => Hook::Clockwise,
+            _ => Hook::CounterClockwise,
+        };
+        Some((
+            action / (DIRECTIONS * HOOKS),
+            (action / HOOKS) % DIRECTIONS,
+            hook,
+        ))
+    }
+
+    pub fn observe(&self) -> Observation {
+        let shape = self.observation_shape();
+        let mut obs = Observation {
+            data: vec![0.0; shape.iter().product()],
+            shape,
+        };
+        let board = &self.position.board;
+        let me = self.position.to_move;
+        // the rules of this episode, whatever `rules` has been set to since
+        let rules = &self.position.rules;
+        // fallen tiles stay on the board while they are to regrow
+        for tile in board.tiles().into_iter().filter(|t| !t.props.dead) {
+            obs.set(0, &tile.coord, 1.0);
+            if let Some(lifespan) = tile.lifespan() {
+                let left = lifespan.saturating_sub(self.position.move_nr);
+                obs.set(1, &tile.coord, left as f32 / self.max_lifespan as f32);
+                if rules.falling_tiles && left <= rules.falling_tiles_heads_up {
+                    obs.set(2, &tile.coord, 1.0);
+                }
+            }
+        }
+        for piece in board.pieces() {
+            let channel = match (piece.otype, piece.owned_by(&me)) {
+                (ObjectType::Wall, _) => 3,
+                (ObjectType::Dasher, true) => 4,
+                (ObjectType::Jumper, true) => 5,
+                (ObjectType::Dasher, false) => 6,
+                (ObjectType::Jumper, false) => 7,
+                (ObjectType::Pusher, true) => 8,
+                (ObjectType::Pusher, false) => 9,
+                (ObjectType::Bomber, true) => 10,
+                (ObjectType::Bomber, false) => 11,
+                (ObjectType::Tile, _) => continue,
+            };
+            obs.set(channel, &piece.coord, 1.0);
+        }
+        obs
+    }
+}
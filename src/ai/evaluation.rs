@@ -0,0 +1,86 @@
+use crate::ai::Position;
+use crate::config::CONF;
+use crate::engine::{ObjectType, Player};
+
+/// score of a won position; everything else stays well below it
+pub const WIN: f32 = 1.0e6;
+
+/**
+Weights for scoring a position from one player's point of view.
+Every term is "mine minus theirs".
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Evaluation {
+    /// per Dasher alive
+    pub dasher: f32,
+    /// per Jumper alive
+    pub jumper: f32,
+    /// per Pusher alive
+    pub pusher: f32,
+    /// per Bomber alive
+    pub bomber: f32,
+    /// per legal move
+    pub mobility: f32,
+    /// per piece standing on a tile that falls within `danger_horizon` moves
+    pub falling: f32,
+    pub danger_horizon: usize,
+}
+
+impl Default for Evaluation {
+    fn default() -> Evaluation {
+        Evaluation {
+            dasher: 10.0,
+            jumper: 10.0,
+            pusher: 8.0,
+            bomber: 6.0,
+            mobility: 0.2,
+            falling: 4.0,
+            danger_horizon: CONF.get().falling_tiles_heads_up,
+        }
+    }
+}
+
+impl Evaluation {
+    pub fn evaluate(&self, position: &Position, player: &Player) -> f32 {
+        match position.winner() {
+            Some(Player::God) => return 0.0,
+            Some(winner) if &winner == player => return WIN,
+            Some(_) => return -WIN,
+            None => {}
+        }
+        let opponent = player.opponent();
+        let mut score = 0.0;
+        for piece in position.board.pieces() {
+            let sign = if piece.owned_by(player) {
+                1.0
+            } else if piece.owned_by(&opponent) {
+                -1.0
+            } else {
+                continue;
+            };
+            score += sign
+                * match piece.otype {
+                    ObjectType::Dasher => self.dasher,
+                    ObjectType::Jumper => self.jumper,
+                    ObjectType::Pusher => self.pusher,
+                    ObjectType::Bomber => self.bomber,
+                    _ => 0.0,
+                };
+            let falls_soon = position.rules.falling_tiles
+                && position
+                    .board
+                    .tile_at(&piece.coord)
+                    .and_then(|t| t.lifespan())
+                    .is_some_and(|l| l <= position.move_nr + self.danger_horizon);
+            if falls_soon {
+                score -= sign * self.falling;
+            }
+        }
+        if self.mobility != 0.0 {
+            let mine = position.legal_moves_for(player).len() as f32;
+            let theirs = position.legal_moves_for(&opponent).len() as f32;
+            score += self.mobility * (mine - theirs);
+        }
+        score
+    }
+}
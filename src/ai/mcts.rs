@@ -0,0 +1,277 @@
+use crate::ai::{Bot, Position};
+use crate::config::CONF;
+use crate::engine::Player;
+use crate::game::moves::Move;
+use crate::rng::Rng;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// how many trees a playout budget is spread over, by default
+const DEFAULT_TREES: usize = 4;
+
+/**
+Monte Carlo Tree Search with random playouts.
+
+Searches until its time budget is spent, or for a set number of playouts.
+Each thread grows a tree of its own from the same position, and the visit
+counts at the root are added up when picking a move. On a playout budget,
+`trees` trees are grown however many threads there are, so the move picked
+follows from the seed alone.
+
+Passing is always an option, since a player can let their time run out.
+*/
+#[derive(Debug, Clone)]
+pub struct Mcts {
+    /// time to think per move; by default, `Config::mcts_think_fraction` of the move timeout
+    pub budget: Option<Duration>,
+    /// playouts per tree; if set, the search stops after these instead of at `budget`
+    pub iterations: Option<usize>,
+    /// trees grown on a playout budget, shared out over `threads`
+    pub trees: usize,
+    pub threads: usize,
+    /// the UCT exploration constant
+    pub exploration: f32,
+    /// chance that a player passes on a turn in a playout, as if their time ran out
+    pub timeout_rate: f32,
+    /// playouts that go on for longer than this are scored as draws
+    pub max_playout: usize,
+    rng: Rng,
+}
+
+struct Node {
+    position: Position,
+    parent: Option<usize>,
+    /// index of the move that led here, in the parent's `actions`
+    action: usize,
+    actions: Vec<Option<Move>>,
+    untried: Vec<usize>,
+    children: Vec<usize>,
+    /// the player who made the move leading here
+    mover: Player,
+    visits: u32,
+    /// playouts won by `mover`, draws count as half
+    wins: f32,
+}
+
+struct Tree<'a> {
+    mcts: &'a Mcts,
+    nodes: Vec<Node>,
+    rng: Rng,
+}
+
+impl Mcts {
+    pub fn new(seed: u64) -> Mcts {
+        Mcts {
+            budget: None,
+            iterations: (CONF.get().mcts_iterations > 0).then_some(CONF.get().mcts_iterations),
+            trees: DEFAULT_TREES,
+            threads: CONF.get().mcts_threads,
+            exploration: std::f32::consts::SQRT_2,
+            timeout_rate: 0.05,
+            max_playout: 500,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn search(&mut self, position: &Position) -> Option<Move> {
+        let actions = actions(position);
+        if actions.len() == 1 {
+            return None;
+        }
+        let budget = self.budget.unwrap_or_else(|| {
+            Duration::from_secs_f32(
+                position.rules.play_move_timeout * CONF.get().mcts_think_fraction,
+            )
+        });
+        let deadline = Instant::now() + budget;
+        let trees = match self.iterations {
+            Some(_) => self.trees.max(1),
+            None => self.threads.max(1),
+        };
+        let seeds: Vec<u64> = (0..trees).map(|_| self.rng.next_u64()).collect();
+        let threads = self.threads.clamp(1, trees);
+        let mcts = &*self;
+        let visits: Vec<Vec<u32>> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|worker| {
+                    let seeds = &seeds;
+                    scope.spawn(move || {
+                        let grow = |&seed| {
+                            let mut tree = Tree::new(mcts, position.clone(), seed);
+                            tree.grow(deadline);
+                            tree.root_visits()
+                        };
+                        let mine = seeds.iter().skip(worker).step_by(threads);
+                        mine.map(grow).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().expect("mcts worker panicked"))
+                .collect()
+        });
+
+        let total = |i: usize| visits.iter().map(|v| v[i]).sum::<u32>();
+        let best = (0..actions.len()).max_by_key(|&i| total(i))?;
+        actions[best].clone()
+    }
+}
+
+impl Bot for Mcts {
+    fn choose_move(&mut self, position: &Position) -> Option<Move> {
+        self.search(position)
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+}
+
+impl<'a> Tree<'a> {
+    fn new(mcts: &'a Mcts, position: Position, seed: u64) -> Tree<'a> {
+        let mut tree = Tree {
+            mcts,
+            nodes: vec![],
+            rng: Rng::new(seed),
+        };
+        let mover = position.to_move.opponent();
+        tree.add_node(position, None, 0, mover);
+        tree
+    }
+
+    /// until `deadline`, or for `Mcts::iterations` playouts if it is set
+    fn grow(&mut self, deadline: Instant) {
+        let more = |tree: &Tree| match tree.mcts.iterations {
+            Some(n) => (tree.nodes[0].visits as usize) < n,
+            None => Instant::now() < deadline,
+        };
+        // always do some work, even if the budget is tiny
+        while self.nodes[0].visits < 1 || more(self) {
+            let leaf = self.select();
+            let node = self.expand(leaf);
+            let winner = self.playout(node);
+            self.backpropagate(node, winner);
+        }
+    }
+
+    fn root_visits(&self) -> Vec<u32> {
+        let root = &self.nodes[0];
+        let mut visits = vec![0; root.actions.len()];
+        for &child in &root.children {
+            visits[self.nodes[child].action] = self.nodes[child].visits;
+        }
+        visits
+    }
+
+    fn add_node(
+        &mut self,
+        position: Position,
+        parent: Option<usize>,
+        action: usize,
+        mover: Player,
+    ) -> usize {
+        let actions = match position.winner() {
+            Some(_) => vec![],
+            None => actions(&position),
+        };
+        self.nodes.push(Node {
+            position,
+            parent,
+            action,
+            untried: (0..actions.len()).collect(),
+            actions,
+            children: vec![],
+            mover,
+            visits: 0,
+            wins: 0.0,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// walks down the fully expanded part of the tree, by UCT
+    fn select(&self) -> usize {
+        let mut current = 0;
+        loop {
+            let node = &self.nodes[current];
+            if !node.untried.is_empty() || node.children.is_empty() {
+                return current;
+            }
+            let log_visits = (node.visits as f32).ln();
+            current = *node
+                .children
+                .iter()
+                .max_by(|&&a, &&b| {
+                    let a = self.uct(a, log_visits);
+                    let b = self.uct(b, log_visits);
+                    a.total_cmp(&b)
+                })
+                .expect("node has children");
+        }
+    }
+
+    fn uct(&self, node: usize, log_parent_visits: f32) -> f32 {
+        let node = &self.nodes[node];
+        let visits = node.visits.max(1) as f32;
+        node.wins / visits + self.mcts.exploration * (log_parent_visits / visits).sqrt()
+    }
+
+    /// adds a random untried child of `node`, if there is one
+    fn expand(&mut self, node: usize) -> usize {
+        if self.nodes[node].untried.is_empty() {
+            return node;
+        }
+        let pick = self.rng.below(self.nodes[node].untried.len());
+        let action = self.nodes[node].untried.swap_remove(pick);
+        let parent = &self.nodes[node];
+        let position = parent.position.play(parent.actions[action].as_ref());
+        let mover = parent.position.to_move;
+        let child = self.add_node(position, Some(node), action, mover);
+        self.nodes[node].children.push(child);
+        child
+    }
+
+    /**
+    plays random moves until the game is over.
+    tiles fall as usual, and now and then a player runs out of time and passes.
+    */
+    fn playout(&mut self, node: usize) -> Option<Player> {
+        let mut position = self.nodes[node].position.clone();
+        for _ in 0..self.mcts.max_playout {
+            if let Some(winner) = position.winner() {
+                return Some(winner);
+            }
+            let moves = position.legal_moves();
+            let r#move = if self.rng.next_f32() < self.mcts.timeout_rate {
+                None
+            } else {
+                self.rng.choose(&moves)
+            };
+            position.advance(r#move);
+        }
+        None
+    }
+
+    fn backpropagate(&mut self, node: usize, winner: Option<Player>) {
+        let mut current = Some(node);
+        while let Some(i) = current {
+            let node = &mut self.nodes[i];
+            node.visits += 1;
+            node.wins += match winner {
+                Some(w) if w == node.mover => 1.0,
+                Some(Player::God) | None => 0.5,
+                Some(_) => 0.0,
+            };
+            current = node.parent;
+        }
+    }
+}
+
+/// every legal move, and passing
+fn actions(position: &Position) -> Vec<Option<Move>> {
+    let mut actions: Vec<Option<Move>> = position.legal_moves().into_iter().map(Some).collect();
+    actions.push(None);
+    actions
+}
@@ -0,0 +1,177 @@
+use crate::ai::{evaluation::WIN, Bot, Evaluation, Position};
+use crate::game::moves::Move;
+use std::collections::HashMap;
+
+/**
+Depth-limited negamax search with alpha-beta pruning.
+
+`depth` counts turns (plies), so a depth of 2 looks at one move of each player.
+
+Positions reached in more than one way (see `Board::hash`) are only searched
+once per search, as long as the first search went deep enough. They may be
+reached at different plies, so wins and losses are kept in the table counted
+from the position itself rather than from the root.
+*/
+#[derive(Debug, Clone)]
+pub struct Minimax {
+    pub depth: usize,
+    pub evaluation: Evaluation,
+}
+
+impl Minimax {
+    pub fn new(depth: usize, evaluation: Evaluation) -> Minimax {
+        Minimax { depth, evaluation }
+    }
+
+    pub fn best_move(&self, position: &Position) -> Option<Move> {
+        let mut best = None;
+        let mut alpha = -f32::INFINITY;
+        let mut table = HashMap::new();
+        for (r#move, child) in Minimax::children(position) {
+            let score = -self.negamax(
+                &child,
+                self.depth.saturating_sub(1),
+                -f32::INFINITY,
+                -alpha,
+                1,
+                &mut table,
+            );
+            if best.is_none() || score > alpha {
+                alpha = score;
+                best = Some(r#move);
+            }
+        }
+        best.flatten()
+    }
+
+    fn negamax(
+        &self,
+        position: &Position,
+        depth: usize,
+        mut alpha: f32,
+        mut beta: f32,
+        ply: usize,
+        table: &mut HashMap<u64, Entry>,
+    ) -> f32 {
+        if depth == 0 || position.winner().is_some() {
+            let score = self.evaluation.evaluate(position, &position.to_move);
+            // prefer quick wins and slow losses
+            return if score.abs() >= WIN {
+                score - score.signum() * ply as f32
+            } else {
+                score
+            };
+        }
+        let hash = position.hash();
+        if let Some(entry) = table.get(&hash).filter(|e| e.depth >= depth) {
+            let score = from_table(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return score,
+                Bound::Lower => alpha = alpha.max(score),
+                Bound::Upper => beta = beta.min(score),
+            }
+            if alpha >= beta {
+                return score;
+            }
+        }
+        let window = (alpha, beta);
+        let mut best = -f32::INFINITY;
+        for (_, child) in Minimax::children(position) {
+            let score = -self.negamax(&child, depth - 1, -beta, -alpha, ply + 1, table);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        let bound = if best <= window.0 {
+            Bound::Upper
+        } else if best >= window.1 {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        table.insert(
+            hash,
+            Entry {
+                depth,
+                score: to_table(best, ply),
+                bound,
+            },
+        );
+        best
+    }
+
+    /**
+    every move with the position it leads to; a pass if there are no moves.
+
+    the ones that cost the opponent the most pieces (and us the fewest) come
+    first, since they are the most likely to be good, then the ones where
+    anything happens at all.
+    */
+    fn children(position: &Position) -> Vec<(Option<Move>, Position)> {
+        let me = position.to_move;
+        let mut moves: Vec<Option<Move>> = position.legal_moves().into_iter().map(Some).collect();
+        if moves.is_empty() {
+            moves.push(None);
+        }
+        let mut children: Vec<((i32, usize), Option<Move>, Position)> = moves
+            .into_iter()
+            .map(|r#move| {
+                let mut child = position.clone();
+                let log = child.advance(r#move.as_ref());
+                let swing = log.losses(&me.opponent()) as i32 - log.losses(&me) as i32;
+                ((swing, log.len()), r#move, child)
+            })
+            .collect();
+        children.sort_by_key(|(key, ..)| std::cmp::Reverse(*key));
+        children.into_iter().map(|(_, m, c)| (m, c)).collect()
+    }
+}
+
+/// a win or loss, found some plies away (see `Minimax::negamax`)
+fn is_decided(score: f32) -> bool {
+    score.abs() >= WIN / 2.0
+}
+
+/// a score found `ply` plies into the search, as it is kept in the table
+fn to_table(score: f32, ply: usize) -> f32 {
+    if is_decided(score) {
+        score + score.signum() * ply as f32
+    } else {
+        score
+    }
+}
+
+/// a score from the table, for a position `ply` plies into the search
+fn from_table(score: f32, ply: usize) -> f32 {
+    if is_decided(score) {
+        score - score.signum() * ply as f32
+    } else {
+        score
+    }
+}
+
+/// how a score in the table relates to the true score of its position
+#[derive(Debug, Clone, Copy)]
+enum Bound {
+    Exact,
+    /// the search was cut off: the true score is at least this
+    Lower,
+    /// nothing beat alpha: the true score is at most this
+    Upper,
+}
+
+/// what searching a position `depth` plies deep came to
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    depth: usize,
+    score: f32,
+    bound: Bound,
+}
+
+impl Bot for Minimax {
+    fn choose_move(&mut self, position: &Position) -> Option<Move> {
+        self.best_move(position)
+    }
+}
@@ -0,0 +1,28 @@
+pub mod env;
+mod evaluation;
+mod mcts;
+mod minimax;
+mod position;
+mod random_bot;
+
+pub use evaluation::Evaluation;
+pub use mcts::Mcts;
+pub use minimax::Minimax;
+pub use position::Position;
+pub use random_bot::RandomBot;
+
+use crate::game::moves::Move;
+
+/**
+A computer player.
+
+Bots are `Send`, so a `GameController` can let them think on a thread of
+their own.
+*/
+pub trait Bot: Send {
+    /// picks a move for the player to move in `position`, or `None` to pass
+    fn choose_move(&mut self, position: &Position) -> Option<Move>;
+
+    /// restarts any randomness the bot uses from `seed`
+    fn reseed(&mut self, _seed: u64) {}
+}
@@ -0,0 +1,81 @@
+use crate::engine::{Board, Player, RuleSet};
+use crate::game::{
+    effect_queue::EffectLog,
+    moves::{legal_moves, Move},
+    rules,
+};
+
+/**
+A bare game position for searching: the board, whose turn it is, which
+move it is (which decides when tiles fall), and the rules of the match.
+*/
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub board: Board,
+    pub to_move: Player,
+    pub move_nr: usize,
+    pub rules: RuleSet,
+}
+
+impl Position {
+    pub fn new(board: &Board, to_move: Player, move_nr: usize, rules: &RuleSet) -> Position {
+        let mut board = board.clone();
+        Position::clean(&mut board);
+        Position {
+            board,
+            to_move,
+            move_nr,
+            rules: *rules,
+        }
+    }
+
+    /// every legal move of every piece `player` has
+    pub fn legal_moves_for(&self, player: &Player) -> Vec<Move> {
+        self.board
+            .pieces()
+            .iter()
+            .filter(|p| p.owned_by(player) && !p.props.dead)
+            .flat_map(|p| legal_moves(p, &self.board, &self.rules))
+            .collect()
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.legal_moves_for(&self.to_move)
+    }
+
+    /**
+    the position after `r#move` is played (or the turn is passed, if `None`),
+    including any tiles falling on this move.
+    */
+    pub fn play(&self, r#move: Option<&Move>) -> Position {
+        let mut position = self.clone();
+        position.advance(r#move);
+        position
+    }
+
+    /// like `play`, but in place; returns what happened on the way
+    pub fn advance(&mut self, r#move: Option<&Move>) -> EffectLog {
+        let log = rules::play_turn(&mut self.board, r#move, self.move_nr, 0.0, 0.0, &self.rules);
+        Position::clean(&mut self.board);
+        self.to_move = self.to_move.opponent();
+        self.move_nr += 1;
+        log
+    }
+
+    /// see `Board::hash`
+    pub fn hash(&self) -> u64 {
+        self.board.hash(self.move_nr, self.to_move, &self.rules)
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        rules::winner(&self.board)
+    }
+
+    /**
+    drops animations, and the dead objects they were kept around for, which
+    only matter on screen. a Regrowing tile that fell stays, to come back.
+    */
+    fn clean(board: &mut Board) {
+        rules::remove_expired(board, f32::INFINITY);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::ai::{Bot, Position};
+use crate::game::moves::Move;
+use crate::rng::Rng;
+
+/**
+Plays a uniformly random legal move; a baseline to measure other bots against.
+*/
+#[derive(Debug, Clone)]
+pub struct RandomBot {
+    rng: Rng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> RandomBot {
+        RandomBot {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Bot for RandomBot {
+    fn choose_move(&mut self, position: &Position) -> Option<Move> {
+        self.rng.choose(&position.legal_moves()).cloned()
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+}
@@ -0,0 +1,77 @@
+use hexaroni::config::{self, ConfigSources};
+use hexaroni::engine::{Board, RuleSet};
+use hexaroni::game::generator::Generator;
+use hexaroni::net::{Server, DEFAULT_PORT};
+use std::{env, path::Path, process};
+
+/**
+```text
+hexaroni-server [--port <port>] [--map <map> | --generate <seed>] [--rules <preset>]
+                [--config <file>] [--set <key>=<value>]...
+```
+
+hosts one game after another, for whoever connects.
+*/
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if let Err(e) = ConfigSources::from_args(&args).and_then(config::load) {
+        eprintln!("unable to load config: {}", e);
+        process::exit(1);
+    }
+    let port = arg_value("--port")
+        .map(|p| {
+            p.parse::<u16>().unwrap_or_else(|_| {
+                eprintln!("bad port: {}", p);
+                process::exit(1);
+            })
+        })
+        .unwrap_or(DEFAULT_PORT);
+    let rules = match arg_value("--rules") {
+        Some(name) => RuleSet::preset(&name).unwrap_or_else(|| {
+            eprintln!("unknown rules: {}", name);
+            process::exit(1);
+        }),
+        None => RuleSet::default(),
+    };
+    let board = match (arg_value("--map"), arg_value("--generate")) {
+        (_, Some(seed)) => seed
+            .parse::<u64>()
+            .ok()
+            .and_then(|s| Generator::default().generate(s, &rules))
+            .unwrap_or_else(|| {
+                eprintln!("unable to generate a board from seed {}", seed);
+                process::exit(1);
+            }),
+        (Some(path), None) => Board::load(Path::new(&path)).unwrap_or_else(|e| {
+            eprintln!("unable to load map {}: {}", path, e);
+            process::exit(1);
+        }),
+        (None, None) => Board::test_square(),
+    };
+    let server = Server::bind(("0.0.0.0", port), board, rules).unwrap_or_else(|e| {
+        eprintln!("unable to listen on port {}: {}", port, e);
+        process::exit(1);
+    });
+    println!("waiting for players on port {}", port);
+    loop {
+        // picks up config changes between games
+        if let Err(e) = config::reload() {
+            eprintln!("config not reloaded: {}", e);
+        }
+        match server.play_game() {
+            Ok(outcome) => println!("game over: {}", outcome.explanation()),
+            Err(e) => eprintln!("game aborted: {}", e),
+        }
+    }
+}
+
+/// the value following `flag` on the command line
+fn arg_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    args.iter().position(|a| a == flag).map(|i| {
+        args.get(i + 1).cloned().unwrap_or_else(|| {
+            eprintln!("{} needs a value", flag);
+            process::exit(1);
+        })
+    })
+}
@@ -0,0 +1,503 @@
+//! Config files, and overrides from the environment and the command line.
+//!
+//! ```text
+//! # comments start with '#'
+//! play_move_timeout = 3.5
+//! dasher_can_fly = true
+//! player_color.A = 1.0 0.43 0.76 1.0
+//! camera_position = 0.5 1.5 -10.0
+//! falling_tiles_indicator = wobble 0.2 37.1
+//! ```
+//!
+//! Each line sets one value; anything not mentioned keeps its default.
+//! Colors are `r g b a`, vectors `x y z`, players `A` or `B`, and the falling
+//! tiles indicator is `wobble <amplitude> <speed>` or `falling`. Time controls
+//! are written as in rules (see `TimeControl::parse`), e.g. `fischer:300+2`.
+//! `Config::default().to_string()` lists every key, with its default value.
+//!
+//! Values are applied in this order, later ones winning:
+//! 1. the defaults
+//! 2. the config file: `--config <file>`, else `$HEXARONI_CONFIG`, else
+//!    `hexaroni.conf` if there is one
+//! 3. environment variables: `HEXARONI_` followed by the key in upper case,
+//!    with `.` as `_`; e.g. `HEXARONI_PLAYER_COLOR_A`
+//! 4. `--set <key>=<value>` on the command line
+
+use crate::config::Config;
+use crate::engine::{statuses::StatusType, ObjectType, Player, TimeControl};
+use glam::{Vec3, Vec4};
+use std::path::{Path, PathBuf};
+use std::{env, fmt, fs};
+
+const DEFAULT_FILE: &str = "hexaroni.conf";
+const ENV_PREFIX: &str = "HEXARONI_";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    Io(String),
+    /// a value that could not be read; `origin` tells where it came from
+    Parse {
+        origin: String,
+        msg: String,
+    },
+    /// a value that was read, but makes no sense
+    Invalid {
+        key: &'static str,
+        msg: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(msg) => write!(f, "{}", msg),
+            ConfigError::Parse { origin, msg } => write!(f, "{}: {}", origin, msg),
+            ConfigError::Invalid { key, msg } => write!(f, "{}: {}", key, msg),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/**
+Where to read the config from.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSources {
+    pub file: Option<PathBuf>,
+    /// `(key, value)` pairs from the command line
+    pub overrides: Vec<(String, String)>,
+}
+
+impl ConfigSources {
+    /**
+    picks out `--config <file>` and `--set <key>=<value>` from the command line,
+    and falls back on `$HEXARONI_CONFIG` or `hexaroni.conf` for the file.
+    */
+    pub fn from_args(args: &[String]) -> Result<ConfigSources, ConfigError> {
+        let mut sources = ConfigSources::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => {
+                    let path = args.next().ok_or_else(|| ConfigError::Parse {
+                        origin: "--config".to_string(),
+                        msg: "needs a file".to_string(),
+                    })?;
+                    sources.file = Some(PathBuf::from(path));
+                }
+                "--set" => {
+                    let setting = args.next().map(|s| s.as_str()).unwrap_or_default();
+                    let (key, value) =
+                        setting.split_once('=').ok_or_else(|| ConfigError::Parse {
+                            origin: "--set".to_string(),
+                            msg: format!("expected <key>=<value>, not '{}'", setting),
+                        })?;
+                    sources
+                        .overrides
+                        .push((key.trim().to_string(), value.trim().to_string()));
+                }
+                _ => {}
+            }
+        }
+        if sources.file.is_none() {
+            sources.file = match env::var(format!("{}CONFIG", ENV_PREFIX)) {
+                Ok(path) => Some(PathBuf::from(path)),
+                Err(_) => Some(PathBuf::from(DEFAULT_FILE)).filter(|p| p.exists()),
+            };
+        }
+        Ok(sources)
+    }
+}
+
+impl Config {
+    pub fn from_sources(sources: &ConfigSources) -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        if let Some(path) = &sources.file {
+            config.apply_file(path)?;
+        }
+        for (key, _) in Config::default().entries() {
+            let var = format!("{}{}", ENV_PREFIX, key.to_uppercase().replace('.', "_"));
+            if let Ok(value) = env::var(&var) {
+                config.set_origin(&key, &value, || var.clone())?;
+            }
+        }
+        for (key, value) in &sources.overrides {
+            config.set_origin(key, value, || format!("--set {}", key))?;
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn apply_file(&mut self, path: &Path) -> Result<(), ConfigError> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| ConfigError::Io(format!("unable to read {}: {}", path.display(), e)))?;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let origin = || format!("{} line {}", path.display(), i + 1);
+            let (key, value) = line.split_once('=').ok_or_else(|| ConfigError::Parse {
+                origin: origin(),
+                msg: format!("expected <key> = <value>, not '{}'", line),
+            })?;
+            self.set_origin(key.trim(), value.trim(), origin)?;
+        }
+        Ok(())
+    }
+
+    fn set_origin(
+        &mut self,
+        key: &str,
+        value: &str,
+        origin: impl Fn() -> String,
+    ) -> Result<(), ConfigError> {
+        self.set(key, value).map_err(|msg| ConfigError::Parse {
+            origin: origin(),
+            msg,
+        })
+    }
+
+    /**
+    sets one value, written as in a config file.
+    */
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "starting_player" => self.starting_player = parse_player(value)?,
+            "game_start_countdown" => self.game_start_countdown = parse(value)?,
+            "play_move_timeout" => self.play_move_timeout = parse(value)?,
+            "time_control" => self.time_control = TimeControl::parse(value)?,
+            "flag_fall_loses" => self.flag_fall_loses = parse(value)?,
+            "move_application_time" => self.move_application_time = parse(value)?,
+            "kill_duration" => self.kill_duration = parse(value)?,
+            "tile_base_color" => self.tile_base_color = parse_vec4(value)?,
+            "tile_dragged_from_color" => self.tile_dragged_from_color = parse_vec4(value)?,
+            "tile_targeted_color" => self.tile_targeted_color = parse_vec4(value)?,
+            "tile_possible_move_color" => self.tile_possible_move_color = parse_vec4(value)?,
+            "tile_lifespan_color" => self.tile_lifespan_color = parse_vec4(value)?,
+            "tile_blast_color" => self.tile_blast_color = parse_vec4(value)?,
+            "tile_ice_color" => self.tile_ice_color = parse_vec4(value)?,
+            "tile_lava_color" => self.tile_lava_color = parse_vec4(value)?,
+            "tile_teleporter_color" => self.tile_teleporter_color = parse_vec4(value)?,
+            "tile_regrowing_color" => self.tile_regrowing_color = parse_vec4(value)?,
+            "camera_up" => self.camera_up = parse_vec3(value)?,
+            "camera_position" => self.camera_position = parse_vec3(value)?,
+            "camera_target" => self.camera_target = parse_vec3(value)?,
+            "render_scale" => self.render_scale = parse(value)?,
+            "falling_tiles_heads_up" => self.falling_tiles_heads_up = parse(value)?,
+            "falling_tiles_indicator" => self.falling_tiles_indicator = parse_indicator(value)?,
+            "dasher_can_fly" => self.dasher_can_fly = parse(value)?,
+            "bomber_fuse_indicator" => self.bomber_fuse_indicator = parse_indicator(value)?,
+            "repetition_draw" => self.repetition_draw = parse(value)?,
+            "no_progress_limit" => self.no_progress_limit = parse(value)?,
+            "ai_depth" => self.ai_depth = parse(value)?,
+            "ai_move_delay" => self.ai_move_delay = parse(value)?,
+            "mcts_think_fraction" => self.mcts_think_fraction = parse(value)?,
+            "mcts_iterations" => self.mcts_iterations = parse(value)?,
+            "mcts_seeded_iterations" => self.mcts_seeded_iterations = parse(value)?,
+            "mcts_threads" => self.mcts_threads = parse(value)?,
+            _ => match key.split_once('.') {
+                Some(("player_color", player)) => {
+                    let player = match player {
+                        "God" => Player::God,
+                        _ => parse_player(player)?,
+                    };
+                    self.player_color.insert(player, parse_vec4(value)?);
+                }
+                Some(("object_color", otype)) => {
+                    let otype = match otype {
+                        "Wall" => ObjectType::Wall,
+                        "Dasher" => ObjectType::Dasher,
+                        "Jumper" => ObjectType::Jumper,
+                        "Pusher" => ObjectType::Pusher,
+                        "Bomber" => ObjectType::Bomber,
+                        _ => return Err(format!("no such object type: '{}'", otype)),
+                    };
+                    self.object_color.insert(otype, parse_vec4(value)?);
+                }
+                _ => return Err(format!("unknown key: '{}'", key)),
+            },
+        }
+        Ok(())
+    }
+
+    /**
+    checks that the values make sense together.
+    */
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        fn check(ok: bool, key: &'static str, msg: &str) -> Result<(), ConfigError> {
+            match ok {
+                true => Ok(()),
+                false => Err(ConfigError::Invalid {
+                    key,
+                    msg: msg.to_string(),
+                }),
+            }
+        }
+        check(
+            self.starting_player != Player::God,
+            "starting_player",
+            "must be A or B",
+        )?;
+        check(
+            self.play_move_timeout > 0.0,
+            "play_move_timeout",
+            "must be positive",
+        )?;
+        check(
+            self.game_start_countdown >= 0.0,
+            "game_start_countdown",
+            "can't be negative",
+        )?;
+        check(
+            (0.0..self.play_move_timeout).contains(&self.move_application_time),
+            "move_application_time",
+            "must be at least 0, and shorter than play_move_timeout",
+        )?;
+        check(
+            self.kill_duration >= 0.0,
+            "kill_duration",
+            "can't be negative",
+        )?;
+        check(self.render_scale > 0.0, "render_scale", "must be positive")?;
+        check(self.camera_up != Vec3::ZERO, "camera_up", "can't be 0 0 0")?;
+        check(
+            self.camera_position != self.camera_target,
+            "camera_position",
+            "can't be the same as camera_target",
+        )?;
+        check(
+            self.repetition_draw != 1,
+            "repetition_draw",
+            "must be 0 (never) or at least 2",
+        )?;
+        check(self.ai_depth > 0, "ai_depth", "must be at least 1")?;
+        check(
+            (0.0..self.play_move_timeout).contains(&self.ai_move_delay),
+            "ai_move_delay",
+            "must be at least 0, and shorter than play_move_timeout",
+        )?;
+        check(
+            self.mcts_think_fraction > 0.0 && self.mcts_think_fraction <= 1.0,
+            "mcts_think_fraction",
+            "must be more than 0, and at most 1",
+        )?;
+        check(
+            self.mcts_seeded_iterations > 0,
+            "mcts_seeded_iterations",
+            "must be at least 1",
+        )?;
+        check(self.mcts_threads > 0, "mcts_threads", "must be at least 1")?;
+        let colors = [
+            self.tile_base_color,
+            self.tile_dragged_from_color,
+            self.tile_targeted_color,
+            self.tile_possible_move_color,
+            self.tile_lifespan_color,
+            self.tile_blast_color,
+            self.tile_ice_color,
+            self.tile_lava_color,
+            self.tile_teleporter_color,
+            self.tile_regrowing_color,
+        ]
+        .into_iter()
+        .chain(self.player_color.values().copied())
+        .chain(self.object_color.values().copied());
+        for color in colors {
+            check(
+                color.min_element() >= 0.0 && color.max_element() <= 1.0,
+                "colors",
+                &format!(
+                    "{} has a component outside of 0..1",
+                    write_vec(&color.to_array())
+                ),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// every key, with its value as it would be written in a config file
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = vec![
+            ("starting_player", format!("{:?}", self.starting_player)),
+            (
+                "game_start_countdown",
+                self.game_start_countdown.to_string(),
+            ),
+            ("play_move_timeout", self.play_move_timeout.to_string()),
+            ("time_control", self.time_control.to_string()),
+            ("flag_fall_loses", self.flag_fall_loses.to_string()),
+            (
+                "move_application_time",
+                self.move_application_time.to_string(),
+            ),
+            ("kill_duration", self.kill_duration.to_string()),
+            (
+                "tile_base_color",
+                write_vec(&self.tile_base_color.to_array()),
+            ),
+            (
+                "tile_dragged_from_color",
+                write_vec(&self.tile_dragged_from_color.to_array()),
+            ),
+            (
+                "tile_targeted_color",
+                write_vec(&self.tile_targeted_color.to_array()),
+            ),
+            (
+                "tile_possible_move_color",
+                write_vec(&self.tile_possible_move_color.to_array()),
+            ),
+            (
+                "tile_lifespan_color",
+                write_vec(&self.tile_lifespan_color.to_array()),
+            ),
+            (
+                "tile_blast_color",
+                write_vec(&self.tile_blast_color.to_array()),
+            ),
+            ("tile_ice_color", write_vec(&self.tile_ice_color.to_array())),
+            (
+                "tile_lava_color",
+                write_vec(&self.tile_lava_color.to_array()),
+            ),
+            (
+                "tile_teleporter_color",
+                write_vec(&self.tile_teleporter_color.to_array()),
+            ),
+            (
+                "tile_regrowing_color",
+                write_vec(&self.tile_regrowing_color.to_array()),
+            ),
+            ("camera_up", write_vec(&self.camera_up.to_array())),
+            (
+                "camera_position",
+                write_vec(&self.camera_position.to_array()),
+            ),
+            ("camera_target", write_vec(&self.camera_target.to_array())),
+            ("render_scale", self.render_scale.to_string()),
+            (
+                "falling_tiles_heads_up",
+                self.falling_tiles_heads_up.to_string(),
+            ),
+            (
+                "falling_tiles_indicator",
+                write_indicator(&self.falling_tiles_indicator),
+            ),
+            ("dasher_can_fly", self.dasher_can_fly.to_string()),
+            (
+                "bomber_fuse_indicator",
+                write_indicator(&self.bomber_fuse_indicator),
+            ),
+            ("repetition_draw", self.repetition_draw.to_string()),
+            ("no_progress_limit", self.no_progress_limit.to_string()),
+            ("ai_depth", self.ai_depth.to_string()),
+            ("ai_move_delay", self.ai_move_delay.to_string()),
+            ("mcts_think_fraction", self.mcts_think_fraction.to_string()),
+            ("mcts_iterations", self.mcts_iterations.to_string()),
+            (
+                "mcts_seeded_iterations",
+                self.mcts_seeded_iterations.to_string(),
+            ),
+            ("mcts_threads", self.mcts_threads.to_string()),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect::<Vec<_>>();
+        for player in [Player::A, Player::B, Player::God] {
+            if let Some(color) = self.player_color.get(&player) {
+                entries.push((
+                    format!("player_color.{:?}", player),
+                    write_vec(&color.to_array()),
+                ));
+            }
+        }
+        for otype in [
+            ObjectType::Wall,
+            ObjectType::Dasher,
+            ObjectType::Jumper,
+            ObjectType::Pusher,
+            ObjectType::Bomber,
+        ] {
+            if let Some(color) = self.object_color.get(&otype) {
+                entries.push((
+                    format!("object_color.{:?}", otype),
+                    write_vec(&color.to_array()),
+                ));
+            }
+        }
+        entries
+    }
+}
+
+/// the config in the file format
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# hexaroni config")?;
+        for (key, value) in self.entries() {
+            writeln!(f, "{} = {}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("bad value: '{}'", value))
+}
+
+fn parse_player(value: &str) -> Result<Player, String> {
+    match value {
+        "A" => Ok(Player::A),
+        "B" => Ok(Player::B),
+        _ => Err(format!("expected A or B, not '{}'", value)),
+    }
+}
+
+fn parse_floats<const N: usize>(value: &str) -> Result<[f32; N], String> {
+    let floats = value
+        .split_whitespace()
+        .map(parse::<f32>)
+        .collect::<Result<Vec<f32>, String>>()?;
+    floats
+        .try_into()
+        .map_err(|_| format!("expected {} numbers, not '{}'", N, value))
+}
+
+fn parse_vec3(value: &str) -> Result<Vec3, String> {
+    parse_floats::<3>(value).map(Vec3::from_array)
+}
+
+fn parse_vec4(value: &str) -> Result<Vec4, String> {
+    parse_floats::<4>(value).map(Vec4::from_array)
+}
+
+fn parse_indicator(value: &str) -> Result<StatusType, String> {
+    let (kind, rest) = value.split_once(' ').unwrap_or((value, ""));
+    match kind {
+        "wobble" => {
+            let [amplitude, speed] = parse_floats::<2>(rest)?;
+            Ok(StatusType::Wobble { amplitude, speed })
+        }
+        "falling" => Ok(StatusType::Falling),
+        _ => Err(format!(
+            "expected 'wobble <amplitude> <speed>' or 'falling', not '{}'",
+            value
+        )),
+    }
+}
+
+fn write_vec(values: &[f32]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn write_indicator(indicator: &StatusType) -> String {
+    match indicator {
+        StatusType::Wobble { amplitude, speed } => format!("wobble {} {}", amplitude, speed),
+        _ => "falling".to_string(),
+    }
+}
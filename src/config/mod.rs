@@ -0,0 +1,178 @@
+/*!
+Every tunable of the game, in one place.
+
+The values in use are found through `CONF`. They start out as
+`Config::default()`, and `load` replaces them with ones read from a config
+file, environment variables and command line flags (see `file` for the
+format). `reload` reads them all again, while the game is running.
+*/
+
+mod file;
+
+pub use file::{ConfigError, ConfigSources};
+
+use crate::engine::{statuses::StatusType, ObjectType, Player, TimeControl};
+use glam::{vec3, vec4, Vec3, Vec4};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+const PINK: Vec4 = vec4(1.00, 0.43, 0.76, 1.00);
+const SKYBLUE: Vec4 = vec4(0.40, 0.75, 1.00, 1.00);
+const RED: Vec4 = vec4(0.90, 0.16, 0.22, 1.00);
+const BLACK: Vec4 = vec4(0.00, 0.00, 0.00, 1.00);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub starting_player: Player,
+    pub player_color: HashMap<Player, Vec4>,
+    pub object_color: HashMap<ObjectType, Vec4>,
+    pub game_start_countdown: f32,
+    pub play_move_timeout: f32,
+    pub time_control: TimeControl,
+    /// whether running out of time loses the game, rather than passing the turn
+    pub flag_fall_loses: bool,
+    pub move_application_time: f32,
+    pub kill_duration: f32,
+    pub tile_base_color: Vec4,
+    pub tile_dragged_from_color: Vec4,
+    pub tile_targeted_color: Vec4,
+    pub tile_possible_move_color: Vec4,
+    pub tile_lifespan_color: Vec4,
+    /// added to tiles hit by a detonation, fading out over `2 * kill_duration`
+    pub tile_blast_color: Vec4,
+    pub tile_ice_color: Vec4,
+    pub tile_lava_color: Vec4,
+    pub tile_teleporter_color: Vec4,
+    pub tile_regrowing_color: Vec4,
+    pub camera_up: Vec3,
+    pub camera_position: Vec3,
+    pub camera_target: Vec3,
+    pub render_scale: f32,
+    pub falling_tiles_heads_up: usize,
+    pub falling_tiles_indicator: StatusType,
+    pub dasher_can_fly: bool,
+    pub bomber_fuse_indicator: StatusType,
+    /// the game is drawn when the same position comes up this many times; 0 for never
+    pub repetition_draw: usize,
+    /// the game is drawn after this many moves in a row without a piece dying; 0 for never
+    pub no_progress_limit: usize,
+    pub ai_depth: usize,
+    pub ai_move_delay: f32,
+    /// the part of `play_move_timeout` an MCTS bot spends thinking
+    pub mcts_think_fraction: f32,
+    /// playouts per tree an MCTS bot makes on each move; 0 to think for a time instead
+    pub mcts_iterations: usize,
+    /// the same, in a game started from a given seed when `mcts_iterations` is 0,
+    /// so the game plays out the same from the seed
+    pub mcts_seeded_iterations: usize,
+    pub mcts_threads: usize,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            starting_player: Player::A,
+            player_color: HashMap::from([
+                (Player::A, PINK),
+                (Player::B, SKYBLUE),
+                (Player::God, BLACK),
+            ]),
+            object_color: HashMap::from([
+                (ObjectType::Wall, vec4(0.06, 0.06, 0.06, 1.0)),
+                (ObjectType::Dasher, BLACK),
+                (ObjectType::Jumper, BLACK),
+                (ObjectType::Pusher, BLACK),
+                (ObjectType::Bomber, BLACK),
+            ]),
+            game_start_countdown: 2.5,
+            play_move_timeout: 5.0,
+            time_control: TimeControl::PerMove,
+            flag_fall_loses: false,
+            move_application_time: 0.25,
+            kill_duration: 0.4,
+            tile_base_color: vec4(0.03, 0.03, 0.03, 1.0),
+            tile_dragged_from_color: RED,
+            tile_targeted_color: RED,
+            tile_possible_move_color: 0.5 * SKYBLUE,
+            tile_lifespan_color: 0.4 * SKYBLUE,
+            tile_blast_color: vec4(1.00, 0.55, 0.10, 1.00),
+            tile_ice_color: vec4(0.55, 0.75, 0.85, 1.00),
+            tile_lava_color: vec4(0.80, 0.18, 0.02, 1.00),
+            tile_teleporter_color: vec4(0.50, 0.15, 0.70, 1.00),
+            tile_regrowing_color: vec4(0.10, 0.35, 0.10, 1.00),
+            camera_up: vec3(0.0, 0.0, 1.0),
+            camera_target: vec3(0.0, 0.0, 0.0),
+            camera_position: -vec3(-0.5, -1.5, 10.0),
+            render_scale: 1.0,
+            falling_tiles_heads_up: 2,
+            falling_tiles_indicator: StatusType::Wobble {
+                amplitude: 0.2,
+                speed: 37.1,
+            },
+            dasher_can_fly: false,
+            bomber_fuse_indicator: StatusType::Wobble {
+                amplitude: 0.3,
+                speed: 23.0,
+            },
+            repetition_draw: 3,
+            no_progress_limit: 60,
+            ai_depth: 3,
+            ai_move_delay: 0.5,
+            mcts_think_fraction: 0.5,
+            mcts_iterations: 0,
+            mcts_seeded_iterations: 1000,
+            mcts_threads: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+        }
+    }
+}
+
+/// the config in use
+pub static CONF: CurrentConfig = CurrentConfig;
+
+static CURRENT: LazyLock<RwLock<Arc<Config>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(Config::default())));
+static SOURCES: Mutex<Option<ConfigSources>> = Mutex::new(None);
+
+/**
+Hands out the config in use.
+
+A config that is replaced lives on for as long as someone holds on to it,
+so code that reads several values from one `get` sees them all from the
+same config.
+*/
+pub struct CurrentConfig;
+
+impl CurrentConfig {
+    pub fn get(&self) -> Arc<Config> {
+        CURRENT.read().unwrap().clone()
+    }
+}
+
+/**
+makes `config` the one in use, after checking it.
+*/
+pub fn set(config: Config) -> Result<(), ConfigError> {
+    config.validate()?;
+    *CURRENT.write().unwrap() = Arc::new(config);
+    Ok(())
+}
+
+/**
+reads the config from `sources`, and uses it from now on.
+on error, the config in use stays as it was.
+*/
+pub fn load(sources: ConfigSources) -> Result<(), ConfigError> {
+    set(Config::from_sources(&sources)?)?;
+    *SOURCES.lock().unwrap() = Some(sources);
+    Ok(())
+}
+
+/**
+reads the config again from where it was last loaded from.
+*/
+pub fn reload() -> Result<(), ConfigError> {
+    let sources = SOURCES.lock().unwrap().clone().unwrap_or_default();
+    set(Config::from_sources(&sources)?)
+}
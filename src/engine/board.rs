@@ -1,12 +1,62 @@
 use std::collections::HashSet;
+use std::fmt;
 
-use crate::engine::{statuses::Status, Object, ObjectType, Player};
-use crate::geometry::HexCoord;
+use crate::engine::board_index::BoardIndex;
+use crate::engine::{statuses::Status, Object, ObjectType, Player, RuleSet, TileType};
+use crate::geometry::{HexCoord, Shape};
 
-#[derive(Clone)]
+/**
+Reasons a set of objects does not make a playable board.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardError {
+    DuplicateTile(HexCoord),
+    DuplicateObject(HexCoord),
+    DuplicateOid(usize),
+    ObjectOffBoard {
+        oid: usize,
+        coord: HexCoord,
+    },
+    NoPieces(Player),
+    /// teleporters come in pairs; this channel has some other number of them
+    UnpairedTeleporter(u8),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::DuplicateTile(c) => write!(f, "duplicate tile at ({}, {})", c.x, c.y),
+            BoardError::DuplicateObject(c) => {
+                write!(f, "more than one piece at ({}, {})", c.x, c.y)
+            }
+            BoardError::DuplicateOid(oid) => write!(f, "duplicate oid: {}", oid),
+            BoardError::ObjectOffBoard { oid, coord } => write!(
+                f,
+                "object {} placed on ({}, {}), where there is no tile",
+                oid, coord.x, coord.y
+            ),
+            BoardError::NoPieces(player) => write!(f, "no pieces for {:?}", player),
+            BoardError::UnpairedTeleporter(channel) => {
+                write!(
+                    f,
+                    "teleporter {} does not have exactly one partner",
+                    channel
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+#[derive(Clone, Debug)]
 pub struct Board {
     pub size: usize,
     objects: Vec<Object>,
+    /// where there are tiles, or were before they fell
+    shape: Shape,
+    /// finds the objects by coord and by oid
+    index: BoardIndex,
 }
 
 impl Board {
@@ -25,20 +75,22 @@ impl Board {
                 oid,
                 otype,
                 HexCoord::new(x, y, board_size),
-                if oid % 2 == 0 { Player::A } else { Player::B },
+                if oid.is_multiple_of(2) {
+                    Player::A
+                } else {
+                    Player::B
+                },
             )
         }
 
         let board_size = 7;
         let tiles: Vec<Object> = (0..board_size)
             .flat_map(move |x| {
-                fn delay(x: usize) -> usize {
-                    ((33.0 * x.abs_diff(3) as f32) / 7.0).round() as usize
-                }
+                let delay = |x: usize| Board::fall_delay(x.abs_diff(3), 33.0 / 7.0);
                 (0..board_size).map(move |y| {
                     let coord = HexCoord::new(x, y, board_size);
                     let lifespan = 48 - delay(x) - delay(y);
-                    Object::new_tile(y * board_size + x, coord, lifespan)
+                    Object::new_tile(y * board_size + x, coord, lifespan, &RuleSet::default())
                 })
             })
             .collect();
@@ -73,11 +125,57 @@ impl Board {
             board_size,
             pieces.iter().chain(tiles.iter()).cloned().collect(),
         )
+        .expect("test_square is a valid board")
+    }
+
+    /**
+    how many moves before the middle of the board a tile `steps` away from it
+    falls, when every step out makes it fall `per_step` moves sooner.
+    */
+    pub fn fall_delay(steps: usize, per_step: f32) -> usize {
+        (per_step * steps as f32).round() as usize
+    }
+
+    pub fn new(size: usize, objects: Vec<Object>) -> Result<Board, BoardError> {
+        let mut board = Board {
+            size,
+            index: BoardIndex::new(size, &objects),
+            objects,
+            shape: Shape::default(),
+        };
+        board.reshape();
+        board.verify()?;
+        Ok(board)
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /**
+    makes the shape the coords that have a tile right now. for when tiles are
+    taken away for good, as in the editor, rather than falling.
+    */
+    pub fn reshape(&mut self) {
+        let cells = self.tiles().into_iter().map(|t| t.coord);
+        self.shape = Shape::new(self.size, cells);
+    }
+
+    /**
+    the coord `distance` steps away in `direction`, if the board has a tile
+    there (or had one, that fell).
+    */
+    pub fn neighbor(
+        &self,
+        coord: &HexCoord,
+        direction: usize,
+        distance: usize,
+    ) -> Option<HexCoord> {
+        self.shape.neighbor(coord, direction, distance)
     }
 
-    pub fn new(size: usize, objects: Vec<Object>) -> Board {
-        Board::verify(&objects);
-        Board { size, objects }
+    pub fn neighbours(&self, coord: &HexCoord, distance: usize) -> Vec<HexCoord> {
+        self.shape.neighbours(coord, distance)
     }
 
     pub fn tiles(&self) -> Vec<&Object> {
@@ -85,6 +183,7 @@ impl Board {
     }
 
     pub fn tiles_mut(&mut self) -> Vec<&mut Object> {
+        self.index.touch_where(&self.objects, |o| o.is_tile());
         self.objects.iter_mut().filter(|o| o.is_tile()).collect()
     }
 
@@ -93,6 +192,7 @@ impl Board {
     }
 
     pub fn pieces_mut(&mut self) -> Vec<&mut Object> {
+        self.index.touch_where(&self.objects, |o| !o.is_tile());
         self.objects.iter_mut().filter(|o| !o.is_tile()).collect()
     }
 
@@ -101,43 +201,157 @@ impl Board {
     }
 
     pub fn objects_mut(&mut self) -> Vec<&mut Object> {
+        self.index.touch_where(&self.objects, |_| true);
         self.objects.iter_mut().collect()
     }
 
+    /**
+    lets `f` change each object in turn, keeping the index (and the hash) up
+    to date as it goes.
+    */
+    pub fn update_objects(&mut self, mut f: impl FnMut(&mut Object)) {
+        self.index.sync(&self.objects);
+        for i in 0..self.objects.len() {
+            f(&mut self.objects[i]);
+            self.index.refresh(i, &self.objects);
+        }
+    }
+
+    /**
+    keeps only the statuses `keep` says yes to, and then only the objects
+    `keep_object` says yes to, looking at them as they are by then.
+    */
+    pub fn retain_statuses(
+        &mut self,
+        mut keep: impl FnMut(&Status) -> bool,
+        mut keep_object: impl FnMut(&Object) -> bool,
+    ) {
+        self.update_objects(|o| o.statuses.retain(&mut keep));
+        let count = self.objects.len();
+        self.objects.retain(|o| keep_object(o));
+        if self.objects.len() != count {
+            self.index.rebuild(&self.objects);
+        }
+    }
+
+    /// everything on `coord`: its tile, its piece, and pieces that died there
+    fn at(&self, coord: &HexCoord) -> impl Iterator<Item = &Object> + '_ {
+        self.index
+            .at(*coord, &self.objects)
+            .map(|i| &self.objects[i])
+    }
+
     pub fn tile_at(&self, coord: &HexCoord) -> Option<&Object> {
-        self.tiles().iter().find(|o| &o.coord == coord).copied()
+        self.at(coord).find(|o| o.is_tile())
     }
 
     pub fn piece_at(&self, coord: &HexCoord) -> Option<&Object> {
-        self.pieces().iter().find(|o| &o.coord == coord).copied()
+        self.at(coord).find(|o| !o.is_tile())
+    }
+
+    pub fn get(&self, oid: usize) -> Option<&Object> {
+        self.index
+            .find(oid, &self.objects)
+            .map(|i| &self.objects[i])
     }
 
     pub fn get_as_mut(&mut self, object: &Object) -> Option<&mut Object> {
+        self.index.sync(&self.objects);
+        let i = self.index.find(object.props.oid, &self.objects)?;
+        self.index.touch(i);
+        Some(&mut self.objects[i])
+    }
+
+    /**
+    the Zobrist hash of the position on move `move_nr`, with `to_move` to
+    move: which pieces are where, and whose; which tiles there are; and what
+    effects objects have lined up, counted by how many moves away they are.
+
+    boards with the same hash are the same position, up to the odd collision.
+    it is kept up to date as the board changes, rather than worked out anew.
+    */
+    pub fn hash(&self, move_nr: usize, to_move: Player, rules: &RuleSet) -> u64 {
+        self.index
+            .zobrist(&self.objects)
+            .hash(move_nr, to_move, rules.falling_tiles)
+    }
+
+    /// an oid that is not used by any object on the board
+    pub fn next_oid(&self) -> usize {
         self.objects
-            .iter_mut()
-            .find(|o| o.props.oid == object.props.oid)
+            .iter()
+            .map(|o| o.props.oid + 1)
+            .max()
+            .unwrap_or_default()
     }
 
     pub fn add_object(&mut self, object: Object) {
+        if object.is_tile() {
+            self.shape.insert(object.coord);
+        }
+        self.index.sync(&self.objects);
         self.objects.push(object);
+        self.index.push(&self.objects);
     }
 
     pub fn remove_object(&mut self, object: &Object) {
         self.objects.retain(|o| o.props.oid != object.props.oid);
+        self.index.rebuild(&self.objects);
+    }
+
+    pub fn remove_dead(&mut self) {
+        self.objects.retain(|o| !o.props.dead);
+        self.index.rebuild(&self.objects);
+    }
+
+    /**
+    remakes the tiles for `rules`, keeping their lifespans.
+    */
+    pub fn apply_rules(&mut self, rules: &RuleSet) {
+        self.update_objects(|tile| {
+            if let Some(lifespan) = tile.lifespan().filter(|_| tile.is_tile()) {
+                *tile = Object::new_tile(tile.props.oid, tile.coord, lifespan, rules)
+                    .with_tile_type(tile.tile_type());
+            }
+        });
+    }
+
+    /// the other live teleporter on the same channel as `tile`, if it is one
+    pub fn teleporter_partner(&self, tile: &Object) -> Option<&Object> {
+        match tile.tile_type() {
+            TileType::Teleporter { .. } => self
+                .tiles()
+                .into_iter()
+                .find(|t| t != &tile && t.tile_type() == tile.tile_type() && !t.props.dead),
+            _ => None,
+        }
     }
 
     pub fn kill_piece_at(&mut self, coord: &HexCoord, status: Option<Status>) {
-        self.pieces_mut()
-            .iter_mut()
-            .filter(|p| &p.coord == coord)
-            .for_each(|p| p.set_killed(status.as_ref()))
+        self.index.sync(&self.objects);
+        let on_coord: Vec<usize> = self.index.at(*coord, &self.objects).collect();
+        for i in on_coord {
+            if !self.objects[i].is_tile() {
+                self.objects[i].set_killed(status.as_ref());
+                self.index.refresh(i, &self.objects);
+            }
+        }
     }
 
+    /**
+    objects that are already dead are left as they are. since Pushers, a piece
+    can be dead and still on a tile, falling after it was pushed off the board;
+    killing it again would give it a second death animation.
+    */
     pub fn kill_all_at(&mut self, coord: &HexCoord, status: Option<Status>) {
-        self.objects_mut()
-            .iter_mut()
-            .filter(|o| &o.coord == coord)
-            .for_each(|o| o.set_killed(status.as_ref()))
+        self.index.sync(&self.objects);
+        let on_coord: Vec<usize> = self.index.at(*coord, &self.objects).collect();
+        for i in on_coord {
+            if !self.objects[i].props.dead {
+                self.objects[i].set_killed(status.as_ref());
+                self.index.refresh(i, &self.objects);
+            }
+        }
     }
 
     pub fn is_empty(&self, coord: &HexCoord) -> bool {
@@ -148,46 +362,64 @@ impl Board {
     }
 
     pub fn contents(&self, coord: &HexCoord) -> Option<&Object> {
-        self.pieces()
-            .iter()
-            .find(|p| &p.coord == coord && !p.props.dead)
-            .cloned()
+        self.at(coord).find(|o| !o.is_tile() && !o.props.dead)
     }
 
     pub fn owner(&self, coord: &HexCoord) -> Option<Player> {
         self.contents(coord).map(|o| o.player)
     }
 
-    fn verify(objects: &[Object]) {
+    /**
+    checks that the board is playable:
+    - at most one tile and one piece on any coord
+    - every object has its own oid
+    - every object stands on a tile
+    - teleporters come in pairs
+    - both players have pieces
+    */
+    pub fn verify(&self) -> Result<(), BoardError> {
+        let objects = &self.objects;
         let tiles: Vec<&Object> = objects.iter().filter(|o| o.is_tile()).collect();
         let non_tiles: Vec<&Object> = objects.iter().filter(|o| !o.is_tile()).collect();
-        let tile_coords: Vec<HexCoord> = tiles.iter().map(|t| t.coord).collect();
+        let tile_coords: HashSet<HexCoord> = tiles.iter().map(|t| t.coord).collect();
         let mut tcoords = HashSet::new();
         let mut ocoords = HashSet::new();
         let mut oids = HashSet::new();
 
-        tiles.iter().for_each(|t| {
+        for t in &tiles {
             if !tcoords.insert(t.coord) {
-                panic!("Duplicate tile coord: {:?}", t.coord);
+                return Err(BoardError::DuplicateTile(t.coord));
             }
-        });
-        non_tiles.iter().for_each(|o| {
+        }
+        for o in &non_tiles {
             if !ocoords.insert(o.coord) {
-                panic!("Duplicate object coord: {:?}", o.coord);
+                return Err(BoardError::DuplicateObject(o.coord));
             }
-        });
-        tiles.iter().chain(non_tiles.iter()).for_each(|o| {
+        }
+        for o in tiles.iter().chain(non_tiles.iter()) {
             if !oids.insert(o.props.oid) {
-                panic!("Duplicate oid: oid={:?}", o.props.oid);
+                return Err(BoardError::DuplicateOid(o.props.oid));
             }
             if !tile_coords.contains(&o.coord) {
-                panic!("Object placed on non-tile: oid={:?}", o.props.oid);
+                return Err(BoardError::ObjectOffBoard {
+                    oid: o.props.oid,
+                    coord: o.coord,
+                });
             }
-        });
+        }
+        for t in &tiles {
+            if let TileType::Teleporter { channel } = t.tile_type() {
+                let pair = tiles.iter().filter(|o| o.tile_type() == t.tile_type());
+                if pair.count() != 2 {
+                    return Err(BoardError::UnpairedTeleporter(channel));
+                }
+            }
+        }
         for player in [Player::A, Player::B] {
             if !objects.iter().any(|o| o.owned_by(&player)) {
-                panic!("No pieces for {:?}", player);
+                return Err(BoardError::NoPieces(player));
             }
         }
+        Ok(())
     }
 }
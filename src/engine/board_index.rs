@@ -0,0 +1,210 @@
+/*!
+Finding objects on a `Board` without looking through all of them.
+
+Objects are found by coord through a chain per cell of the grid, linking the
+objects on it in the order the board keeps them, and by oid through a table
+with a slot per oid. Both are plain `Vec`s, so a board clones as cheaply as
+it can.
+
+Along with that, it keeps what each object adds to the Zobrist hash of the
+board (see `zobrist`), and their sum.
+
+The board hands out `&mut Object`s, and whoever has one may move the object
+or change it in any other way. The index can't see that happen, so it is told
+which objects were handed out, and looks at those as they are right now until
+the next `sync`, which catches up with each of them. Changes to many objects at
+once are best made through `refresh`, one object at a time, so that lookups in
+between don't have to look at them all.
+*/
+use crate::engine::{zobrist::Zobrist, Object};
+use crate::geometry::HexCoord;
+use itertools::Itertools;
+
+#[derive(Clone, Debug, Default)]
+pub struct BoardIndex {
+    size: usize,
+    /// per cell, the first object on it
+    head: Vec<Option<usize>>,
+    /// per object, the next object on the same cell
+    next: Vec<Option<usize>>,
+    /// per object, the cell it is linked into
+    cell: Vec<Option<usize>>,
+    /// per oid, the object that has it
+    oids: Vec<Option<usize>>,
+    /// per object, what it adds to the hash
+    hashes: Vec<Zobrist>,
+    /// the hash of all objects that were linked
+    hash: Zobrist,
+    /// the objects that were handed out mutably since the index was last in
+    /// sync, in order
+    stale: Vec<usize>,
+}
+
+impl BoardIndex {
+    pub fn new(size: usize, objects: &[Object]) -> BoardIndex {
+        let mut index = BoardIndex {
+            size,
+            ..Default::default()
+        };
+        index.rebuild(objects);
+        index
+    }
+
+    /// the indices of the objects on `coord`, in order
+    pub fn at<'a>(
+        &'a self,
+        coord: HexCoord,
+        objects: &'a [Object],
+    ) -> impl Iterator<Item = usize> + 'a {
+        let chain = self.cell_of(&coord).and_then(|c| self.head[c]);
+        let linked = std::iter::successors(chain, |&i| self.next[i]);
+        // stale objects may be linked where they no longer are, so they are
+        // left out of the chain and merged back in by where they are now
+        let stale = &self.stale;
+        let mut linked = linked
+            .filter(move |i| stale.binary_search(i).is_err())
+            .peekable();
+        let mut stale = stale.iter().copied().peekable();
+        std::iter::from_fn(move || match (linked.peek(), stale.peek()) {
+            (Some(l), Some(s)) if s < l => stale.next(),
+            (Some(_), _) => linked.next(),
+            (None, _) => stale.next(),
+        })
+        .filter(move |&i| objects[i].coord == coord)
+    }
+
+    /// the index of the object with `oid`
+    pub fn find(&self, oid: usize, objects: &[Object]) -> Option<usize> {
+        let has_oid = |i: &usize| objects[*i].props.oid == oid;
+        let indexed = self.oids.get(oid).copied().flatten().filter(has_oid);
+        match self.stale.iter().copied().find(has_oid) {
+            Some(i) => Some(indexed.map_or(i, |j| j.min(i))),
+            None => indexed,
+        }
+    }
+
+    /// the hash of the board, as it is right now
+    pub fn zobrist(&self, objects: &[Object]) -> Zobrist {
+        self.stale.iter().fold(self.hash, |hash, &i| {
+            hash - self.hashes[i] + Zobrist::of(&objects[i])
+        })
+    }
+
+    /// `objects[i]` was handed out mutably
+    pub fn touch(&mut self, i: usize) {
+        if let Err(at) = self.stale.binary_search(&i) {
+            self.stale.insert(at, i);
+        }
+    }
+
+    /// the objects for which `handed_out` says yes were handed out mutably
+    pub fn touch_where(&mut self, objects: &[Object], handed_out: impl Fn(&Object) -> bool) {
+        let stale = std::mem::take(&mut self.stale);
+        self.stale = (0..objects.len())
+            .filter(|&i| handed_out(&objects[i]))
+            .merge(stale)
+            .dedup()
+            .collect();
+    }
+
+    /// catches up with what happened to the objects that were handed out
+    pub fn sync(&mut self, objects: &[Object]) {
+        for i in std::mem::take(&mut self.stale) {
+            self.refresh(i, objects);
+        }
+    }
+
+    /// catches up with whatever happened to `objects[i]`
+    pub fn refresh(&mut self, i: usize, objects: &[Object]) {
+        let zobrist = Zobrist::of(&objects[i]);
+        let cell = self.cell_of(&objects[i].coord);
+        if cell != self.cell[i] {
+            self.unlink(i);
+            self.link(i, objects);
+        } else if zobrist != self.hashes[i] {
+            // still where it was; only what it adds to the hash changed
+            self.hash = self.hash - self.hashes[i] + zobrist;
+            self.hashes[i] = zobrist;
+        }
+    }
+
+    /// an object was added at the end of `objects`; the index must be in sync
+    pub fn push(&mut self, objects: &[Object]) {
+        let i = objects.len() - 1;
+        self.next.push(None);
+        self.cell.push(None);
+        self.hashes.push(Zobrist::default());
+        self.link(i, objects);
+    }
+
+    /// for when objects were taken out, and the rest moved up
+    pub fn rebuild(&mut self, objects: &[Object]) {
+        self.head = vec![None; self.size * self.size];
+        self.next = vec![None; objects.len()];
+        self.cell = vec![None; objects.len()];
+        self.hashes = vec![Zobrist::default(); objects.len()];
+        self.hash = Zobrist::default();
+        self.oids.clear();
+        self.stale.clear();
+        for i in 0..objects.len() {
+            self.link(i, objects);
+        }
+    }
+
+    fn cell_of(&self, coord: &HexCoord) -> Option<usize> {
+        (coord.x < self.size && coord.y < self.size).then(|| coord.y * self.size + coord.x)
+    }
+
+    /// puts `objects[i]` into the chain of its cell, the oid table and the hash
+    fn link(&mut self, i: usize, objects: &[Object]) {
+        let object = &objects[i];
+        self.hashes[i] = Zobrist::of(object);
+        self.hash = self.hash + self.hashes[i];
+        let oid = object.props.oid;
+        if self.oids.len() <= oid {
+            self.oids.resize(oid + 1, None);
+        }
+        if self.oids[oid].is_none_or(|j| j > i || objects[j].props.oid != oid) {
+            self.oids[oid] = Some(i);
+        }
+        let Some(cell) = self.cell_of(&object.coord) else {
+            return;
+        };
+        self.cell[i] = Some(cell);
+        match self.head[cell] {
+            Some(first) if first < i => {
+                let mut prev = first;
+                while let Some(n) = self.next[prev].filter(|&n| n < i) {
+                    prev = n;
+                }
+                self.next[i] = self.next[prev];
+                self.next[prev] = Some(i);
+            }
+            head => {
+                self.next[i] = head;
+                self.head[cell] = Some(i);
+            }
+        }
+    }
+
+    fn unlink(&mut self, i: usize) {
+        self.hash = self.hash - self.hashes[i];
+        self.hashes[i] = Zobrist::default();
+        let Some(cell) = self.cell[i].take() else {
+            return;
+        };
+        if self.head[cell] == Some(i) {
+            self.head[cell] = self.next[i];
+        } else {
+            let mut prev = self.head[cell];
+            while let Some(p) = prev {
+                if self.next[p] == Some(i) {
+                    self.next[p] = self.next[i];
+                    break;
+                }
+                prev = self.next[p];
+            }
+        }
+        self.next[i] = None;
+    }
+}
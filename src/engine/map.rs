@@ -0,0 +1,325 @@
+//! Map files: a plain-text description of a board that can be edited by hand.
+//!
+//! ```text
+//! # comments start with '#'
+//! size 4
+//!
+//! tiles
+//! 30 32 32 30
+//!   32 40 40 32
+//!     32 40 40 32
+//!       30 32 32 30
+//!
+//! pieces
+//! D  .  .  j
+//!   .  W  .  .
+//!     .  .  W  .
+//!       J  .  .  d
+//! ```
+//!
+//! - `size` is the `board_size`; both grids must be `size` by `size`.
+//! - each grid row is one `y`, and each entry in it one `x`. Rows may be
+//!   indented (to look like the board), whitespace is ignored.
+//! - `tiles` holds the lifespan of each tile: the move on which it falls.
+//!   `.` means there is no tile there. A letter after the lifespan gives the
+//!   tile a type: `i` for ice, `l` for lava, `r` for a regrowing tile, and
+//!   `t` followed by a channel (e.g. `30t1`) for a teleporter. The two
+//!   teleporters of a channel are each other's partner.
+//! - `pieces` holds what stands on each tile: `W` for a wall, `D`/`J`/`P`/`B`
+//!   for a Dasher/Jumper/Pusher/Bomber of `Player::A`, `d`/`j`/`p`/`b` for
+//!   those of `Player::B`, and `.` for nothing.
+//!
+//! The tiles can make any shape: a hexagon, or something with holes in it.
+//! Nothing moves through a `.`, as there is nowhere to go. A map loaded from
+//! a file is moved to the middle of its grid, so it is centred on screen.
+
+use crate::engine::{board::BoardError, Board, Object, ObjectType, Player, RuleSet, TileType};
+use crate::geometry::{HexCoord, Shape};
+use std::{fmt, fs, path::Path};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapError {
+    Io(String),
+    Syntax { line: usize, msg: String },
+    MissingSection(&'static str),
+    Invalid(BoardError),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::Io(msg) => write!(f, "{}", msg),
+            MapError::Syntax { line, msg } => write!(f, "line {}: {}", line, msg),
+            MapError::MissingSection(s) => write!(f, "missing section: {}", s),
+            MapError::Invalid(e) => write!(f, "invalid board: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<BoardError> for MapError {
+    fn from(e: BoardError) -> MapError {
+        MapError::Invalid(e)
+    }
+}
+
+/// the biggest map a file may hold; only there to catch mistakes, since
+/// `game::notation` names columns past `z` as `aa`, `ab`, ...
+const MAX_SIZE: usize = 64;
+
+enum Section {
+    None,
+    Tiles,
+    Pieces,
+}
+
+impl Board {
+    pub fn load(path: &Path) -> Result<Board, MapError> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| MapError::Io(format!("unable to read {}: {}", path.display(), e)))?;
+        Board::parse_map(&text, true)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), MapError> {
+        fs::write(path, self.to_map())
+            .map_err(|e| MapError::Io(format!("unable to write {}: {}", path.display(), e)))
+    }
+
+    /// the board as written; unlike `load`, the tiles are not moved to the middle
+    pub fn from_map(text: &str) -> Result<Board, MapError> {
+        Board::parse_map(text, false)
+    }
+
+    fn parse_map(text: &str, centre: bool) -> Result<Board, MapError> {
+        let mut size = None;
+        let mut section = Section::None;
+        let mut tile_rows: Vec<(usize, Vec<String>)> = vec![];
+        let mut piece_rows: Vec<(usize, Vec<String>)> = vec![];
+
+        for (i, line) in text.lines().enumerate() {
+            let line_nr = i + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(n) = line.strip_prefix("size") {
+                let n = n.trim().parse::<usize>().map_err(|_| MapError::Syntax {
+                    line: line_nr,
+                    msg: format!("bad size: '{}'", n.trim()),
+                })?;
+                size = Some(n);
+                continue;
+            }
+            match line {
+                "tiles" => section = Section::Tiles,
+                "pieces" => section = Section::Pieces,
+                _ => {
+                    let entries = line.split_whitespace().map(String::from).collect();
+                    match section {
+                        Section::Tiles => tile_rows.push((line_nr, entries)),
+                        Section::Pieces => piece_rows.push((line_nr, entries)),
+                        Section::None => {
+                            return Err(MapError::Syntax {
+                                line: line_nr,
+                                msg: format!("unexpected '{}' outside of a section", line),
+                            })
+                        }
+                    }
+                }
+            }
+        }
+
+        let size = size.ok_or(MapError::MissingSection("size"))?;
+        if size == 0 || size > MAX_SIZE {
+            return Err(MapError::Syntax {
+                line: 0,
+                msg: format!("size must be between 1 and {}, not {}", MAX_SIZE, size),
+            });
+        }
+        if tile_rows.is_empty() {
+            return Err(MapError::MissingSection("tiles"));
+        }
+        check_grid(&tile_rows, size, "tiles")?;
+        if !piece_rows.is_empty() {
+            check_grid(&piece_rows, size, "pieces")?;
+        }
+
+        let (grid, dx, dy) = match centre {
+            true => {
+                let cells = tile_rows.iter().enumerate().flat_map(|(y, (_, row))| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|(_, e)| *e != ".")
+                        .map(move |(x, _)| HexCoord::new(x, y, size))
+                });
+                Shape::new(size, cells).centring(MAX_SIZE)
+            }
+            false => (size, 0, 0),
+        };
+        // pieces off the shifted grid had no tile to stand on anyway
+        let at = |x: usize, y: usize| -> Option<HexCoord> {
+            let (x, y) = (x as i32 + dx, y as i32 + dy);
+            let on_grid = (0..grid as i32).contains(&x) && (0..grid as i32).contains(&y);
+            on_grid.then(|| HexCoord::new(x as usize, y as usize, grid))
+        };
+
+        let mut objects = vec![];
+        for (y, (line, row)) in tile_rows.iter().enumerate() {
+            for (x, entry) in row.iter().enumerate() {
+                if entry == "." {
+                    continue;
+                }
+                let (lifespan, tile_type) = parse_tile(entry).ok_or(MapError::Syntax {
+                    line: *line,
+                    msg: format!("bad tile: '{}'", entry),
+                })?;
+                let oid = objects.len();
+                // the shift keeps every tile on the grid
+                let coord = at(x, y).unwrap();
+                let tile = Object::new_tile(oid, coord, lifespan, &RuleSet::default());
+                objects.push(tile.with_tile_type(tile_type));
+            }
+        }
+        for (y, (line, row)) in piece_rows.iter().enumerate() {
+            for (x, entry) in row.iter().enumerate() {
+                let coord = match (entry.as_str(), at(x, y)) {
+                    (".", _) => continue,
+                    (_, Some(coord)) => coord,
+                    (_, None) => {
+                        return Err(MapError::Syntax {
+                            line: *line,
+                            msg: format!("'{}' has no tile to stand on", entry),
+                        })
+                    }
+                };
+                let oid = objects.len();
+                let object = match entry.as_str() {
+                    "W" => Object::new_wall(oid, coord),
+                    "D" => Object::new(oid, ObjectType::Dasher, coord, Player::A),
+                    "J" => Object::new(oid, ObjectType::Jumper, coord, Player::A),
+                    "P" => Object::new(oid, ObjectType::Pusher, coord, Player::A),
+                    "B" => Object::new(oid, ObjectType::Bomber, coord, Player::A),
+                    "d" => Object::new(oid, ObjectType::Dasher, coord, Player::B),
+                    "j" => Object::new(oid, ObjectType::Jumper, coord, Player::B),
+                    "p" => Object::new(oid, ObjectType::Pusher, coord, Player::B),
+                    "b" => Object::new(oid, ObjectType::Bomber, coord, Player::B),
+                    _ => {
+                        return Err(MapError::Syntax {
+                            line: *line,
+                            msg: format!("unknown piece: '{}'", entry),
+                        })
+                    }
+                };
+                objects.push(object);
+            }
+        }
+        Ok(Board::new(grid, objects)?)
+    }
+
+    /**
+    the board in the map format; dead objects are left out.
+    */
+    pub fn to_map(&self) -> String {
+        let tiles = self.grid(|coord| match self.tile_at(coord) {
+            Some(t) if !t.props.dead => match t.lifespan() {
+                Some(l) => format!("{}{}", l, tile_suffix(&t.tile_type())),
+                None => ".".to_string(),
+            },
+            _ => ".".to_string(),
+        });
+        let pieces = self.grid(|coord| match self.contents(coord) {
+            Some(p) => piece_symbol(p).to_string(),
+            None => ".".to_string(),
+        });
+        format!(
+            "# hexaroni map\nsize {}\n\ntiles\n{}\npieces\n{}",
+            self.size, tiles, pieces
+        )
+    }
+
+    fn grid(&self, entry: impl Fn(&HexCoord) -> String) -> String {
+        let rows: Vec<Vec<String>> = (0..self.size)
+            .map(|y| {
+                (0..self.size)
+                    .map(|x| entry(&HexCoord::new(x, y, self.size)))
+                    .collect()
+            })
+            .collect();
+        let width = rows.iter().flatten().map(|e| e.len()).max().unwrap_or(1);
+        let mut text = String::new();
+        for (y, row) in rows.iter().enumerate() {
+            // every row is shifted by about half a tile, like on screen
+            text.push_str(&" ".repeat(y * (width + 1) / 2));
+            let entries: Vec<String> = row.iter().map(|e| format!("{:>width$}", e)).collect();
+            text.push_str(entries.join(" ").trim_end());
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn check_grid(
+    rows: &[(usize, Vec<String>)],
+    size: usize,
+    section: &'static str,
+) -> Result<(), MapError> {
+    if rows.len() != size {
+        return Err(MapError::Syntax {
+            line: rows.last().map(|(l, _)| *l).unwrap_or_default(),
+            msg: format!("{} has {} rows, expected {}", section, rows.len(), size),
+        });
+    }
+    for (line, row) in rows {
+        if row.len() != size {
+            return Err(MapError::Syntax {
+                line: *line,
+                msg: format!("row has {} entries, expected {}", row.len(), size),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// `<lifespan>` followed by the tile type, if it is not ground
+fn parse_tile(entry: &str) -> Option<(usize, TileType)> {
+    let digits = entry
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(entry.len());
+    let (lifespan, suffix) = entry.split_at(digits);
+    let tile_type = match suffix {
+        "" => TileType::Ground,
+        "i" => TileType::Ice,
+        "l" => TileType::Lava,
+        "r" => TileType::Regrowing,
+        _ => TileType::Teleporter {
+            channel: suffix.strip_prefix('t')?.parse().ok()?,
+        },
+    };
+    Some((lifespan.parse().ok()?, tile_type))
+}
+
+fn tile_suffix(tile_type: &TileType) -> String {
+    match tile_type {
+        TileType::Ground => String::new(),
+        TileType::Ice => "i".to_string(),
+        TileType::Lava => "l".to_string(),
+        TileType::Regrowing => "r".to_string(),
+        TileType::Teleporter { channel } => format!("t{}", channel),
+    }
+}
+
+fn piece_symbol(piece: &Object) -> char {
+    let symbol = match piece.otype {
+        ObjectType::Wall => return 'W',
+        ObjectType::Dasher => 'D',
+        ObjectType::Jumper => 'J',
+        ObjectType::Pusher => 'P',
+        ObjectType::Bomber => 'B',
+        ObjectType::Tile => '.',
+    };
+    match piece.player {
+        Player::B => symbol.to_ascii_lowercase(),
+        _ => symbol,
+    }
+}
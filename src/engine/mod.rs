@@ -1,10 +1,18 @@
 mod board;
+mod board_index;
+mod map;
 mod objects;
 mod player;
+mod rule_set;
 pub mod statuses;
 mod tile_type;
+mod time_control;
+mod zobrist;
 
-pub use board::Board;
+pub use board::{Board, BoardError};
+pub use map::MapError;
 pub use objects::{Object, ObjectProps, ObjectType};
 pub use player::Player;
+pub use rule_set::RuleSet;
 pub use tile_type::TileType;
+pub use time_control::{TimeBank, TimeControl};
@@ -2,9 +2,10 @@ use crate::config::CONF;
 use crate::engine::{
     objects::ObjectProps,
     statuses::{Effect, Status, StatusType},
-    ObjectType, Player,
+    ObjectType, Player, RuleSet, TileType,
 };
 use crate::geometry::{HexCoord, ScreenCoord};
+use std::mem;
 
 #[derive(Clone, Debug)]
 pub struct Object {
@@ -35,7 +36,7 @@ impl Object {
         }
     }
 
-    pub fn new_tile(oid: usize, coord: HexCoord, lifespan: usize) -> Object {
+    pub fn new_tile(oid: usize, coord: HexCoord, lifespan: usize, rules: &RuleSet) -> Object {
         let mut tile = Object {
             otype: ObjectType::Tile,
             coord,
@@ -56,16 +57,21 @@ impl Object {
                 apply: Some(Box::new(StatusType::Falling)),
                 duration: Some(2.0),
             },
-            lifespan - CONF.falling_tiles_heads_up,
+            lifespan.saturating_sub(rules.falling_tiles_heads_up),
             Effect::SetStatus {
                 object: tile.clone(),
-                stype: Box::new(CONF.falling_tiles_indicator.clone()),
+                stype: Box::new(CONF.get().falling_tiles_indicator.clone()),
                 duration: None,
             },
         ));
         tile
     }
 
+    pub fn with_tile_type(mut self, tile_type: TileType) -> Object {
+        self.props.tile_type = tile_type;
+        self
+    }
+
     pub fn new_wall(oid: usize, coord: HexCoord) -> Object {
         Object {
             otype: ObjectType::Wall,
@@ -101,7 +107,7 @@ impl Object {
             } = delayed.stype.clone()
             {
                 if indicator_move_nr.unwrap_or(usize::MAX) == curr_move_nr {
-                    effects.push(indicator.unwrap_or_else(|| {
+                    effects.push(*indicator.unwrap_or_else(|| {
                         panic!(
                             "no indicator for delayed effect with indicator_move_nr set: {:?}",
                             delayed
@@ -111,17 +117,33 @@ impl Object {
 
                 if move_nr == curr_move_nr {
                     self.remove_status(&delayed.stype.clone());
-                    effects.push(effect);
+                    effects.push(*effect);
                 }
             }
         }
         effects
     }
 
+    /**
+    the move on which a tile falls, if it is going to.
+    */
+    pub fn lifespan(&self) -> Option<usize> {
+        self.statuses.iter().find_map(|s| match &s.stype {
+            StatusType::DelayedEffect {
+                move_nr, effect, ..
+            } if matches!(**effect, Effect::KillAallOn { .. }) => Some(*move_nr),
+            _ => None,
+        })
+    }
+
     pub fn is_tile(&self) -> bool {
         self.otype == ObjectType::Tile
     }
 
+    pub fn tile_type(&self) -> TileType {
+        self.props.tile_type
+    }
+
     pub fn owned_by(&self, player: &Player) -> bool {
         player == &self.player
     }
@@ -147,12 +169,19 @@ impl Object {
         statuses.iter().for_each(|s| self.add_status(s))
     }
 
-    pub fn remove_status(&mut self, _stype: &StatusType) {
-        self.statuses.retain(|s| !matches!(&s.stype, _stype));
+    /// removes every status of the same kind as `stype`, whatever its params
+    pub fn remove_status(&mut self, stype: &StatusType) {
+        self.statuses
+            .retain(|s| mem::discriminant(&s.stype) != mem::discriminant(stype));
     }
 
+    /**
+    statuses that never end are dropped, so the object can be removed once
+    its animations are over.
+    */
     pub fn set_killed(&mut self, status: Option<&Status>) {
         self.props.dead = true;
+        self.statuses.retain(|s| s.duration.is_some());
         if let Some(s) = status {
             self.add_status(s);
         }
@@ -162,7 +191,9 @@ impl Object {
     gets the actual status (with correct params)
     from a status with any (e.g. default) params
     */
-    fn get_status(&self, _stype: &StatusType) -> Option<&Status> {
-        self.statuses.iter().find(|s| matches!(&s.stype, _stype))
+    fn get_status(&self, stype: &StatusType) -> Option<&Status> {
+        self.statuses
+            .iter()
+            .find(|s| mem::discriminant(&s.stype) == mem::discriminant(stype))
     }
 }
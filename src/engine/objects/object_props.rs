@@ -1,3 +1,5 @@
+use crate::engine::TileType;
+
 #[derive(Clone, Copy, Debug)]
 pub struct ObjectProps {
     pub oid: usize,
@@ -5,6 +7,8 @@ pub struct ObjectProps {
     pub draggable: bool,
     pub dead: bool,
     pub size: f32,
+    /// only means anything for tiles
+    pub tile_type: TileType,
 }
 
 impl Default for ObjectProps {
@@ -15,6 +19,7 @@ impl Default for ObjectProps {
             draggable: true,
             dead: false,
             size: 1.0,
+            tile_type: TileType::Ground,
         }
     }
 }
@@ -26,6 +31,7 @@ impl ObjectProps {
         draggable: bool,
         dead: bool,
         size: f32,
+        tile_type: TileType,
     ) -> ObjectProps {
         ObjectProps {
             oid,
@@ -33,6 +39,7 @@ impl ObjectProps {
             draggable,
             dead,
             size,
+            tile_type,
         }
     }
 }
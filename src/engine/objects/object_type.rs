@@ -2,6 +2,8 @@
 pub enum ObjectType {
     Dasher,
     Jumper,
+    Pusher,
+    Bomber,
     Wall,
     Tile,
 }
@@ -0,0 +1,193 @@
+use crate::config::CONF;
+use crate::engine::{Player, TimeControl};
+use std::fmt;
+
+/**
+The rules a match is played by.
+
+Each `GameController` owns one, so matches with different rules can run side
+by side. The "standard" rules come from the config; the other presets each
+change one thing about them.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuleSet {
+    pub name: &'static str,
+    pub starting_player: Player,
+    /// seconds a player has for a move, when not on a clock (see `TimeControl`)
+    pub play_move_timeout: f32,
+    pub time_control: TimeControl,
+    /// whether running out of time loses the game, rather than passing the turn
+    pub flag_fall_loses: bool,
+    /// whether Dashers can cross gaps where there are no tiles
+    pub dasher_can_fly: bool,
+    /// whether tiles fall when their lifespan is up
+    pub falling_tiles: bool,
+    /// how many moves ahead of falling a tile starts to show it
+    pub falling_tiles_heads_up: usize,
+    /// how many moves after being lit a Bomber blows up
+    pub bomber_fuse: usize,
+    /// how many moves a piece survives standing on lava
+    pub lava_turns: usize,
+    /// how many moves after falling a Regrowing tile comes back
+    pub regrow_turns: usize,
+    /// the game is drawn when the same position comes up this many times; 0 for never
+    pub repetitions: usize,
+    /// the game is drawn after this many moves in a row without a piece dying; 0 for never
+    pub no_progress_limit: usize,
+}
+
+impl Default for RuleSet {
+    fn default() -> RuleSet {
+        RuleSet::standard()
+    }
+}
+
+impl RuleSet {
+    pub fn standard() -> RuleSet {
+        RuleSet {
+            name: "standard",
+            starting_player: CONF.get().starting_player,
+            play_move_timeout: CONF.get().play_move_timeout,
+            time_control: CONF.get().time_control,
+            flag_fall_loses: CONF.get().flag_fall_loses,
+            dasher_can_fly: CONF.get().dasher_can_fly,
+            falling_tiles: true,
+            falling_tiles_heads_up: CONF.get().falling_tiles_heads_up,
+            bomber_fuse: 2,
+            lava_turns: 4,
+            regrow_turns: 10,
+            repetitions: CONF.get().repetition_draw,
+            no_progress_limit: CONF.get().no_progress_limit,
+        }
+    }
+
+    pub fn blitz() -> RuleSet {
+        RuleSet {
+            name: "blitz",
+            play_move_timeout: 2.0,
+            ..RuleSet::standard()
+        }
+    }
+
+    /// five minutes each, two seconds more per move, and no time to lose
+    pub fn rapid() -> RuleSet {
+        RuleSet {
+            name: "rapid",
+            time_control: TimeControl::Fischer {
+                main: 300.0,
+                increment: 2.0,
+            },
+            flag_fall_loses: true,
+            ..RuleSet::standard()
+        }
+    }
+
+    pub fn no_falling_tiles() -> RuleSet {
+        RuleSet {
+            name: "no-falling-tiles",
+            falling_tiles: false,
+            ..RuleSet::standard()
+        }
+    }
+
+    pub fn flying_dashers() -> RuleSet {
+        RuleSet {
+            name: "flying-dashers",
+            dasher_can_fly: true,
+            ..RuleSet::standard()
+        }
+    }
+
+    pub fn presets() -> [RuleSet; 5] {
+        [
+            RuleSet::standard(),
+            RuleSet::blitz(),
+            RuleSet::rapid(),
+            RuleSet::no_falling_tiles(),
+            RuleSet::flying_dashers(),
+        ]
+    }
+
+    pub fn preset(name: &str) -> Option<RuleSet> {
+        RuleSet::presets().into_iter().find(|r| r.name == name)
+    }
+
+    /// the preset after this one, for flipping through them
+    pub fn next_preset(&self) -> RuleSet {
+        let presets = RuleSet::presets();
+        let i = presets.iter().position(|r| r.name == self.name);
+        presets[i.map(|i| (i + 1) % presets.len()).unwrap_or_default()]
+    }
+
+    /**
+    reads rules written by `Display`: a preset name, followed by
+    `key=value` pairs for anything that differs from it (here).
+    */
+    pub fn parse(text: &str) -> Result<RuleSet, String> {
+        let mut words = text.split_whitespace();
+        let name = words.next().ok_or("no rules given")?;
+        let mut rules = RuleSet::preset(name).ok_or(format!("unknown rules: '{}'", name))?;
+        for word in words {
+            let (key, value) = word
+                .split_once('=')
+                .ok_or(format!("expected <key>=<value>, not '{}'", word))?;
+            let bad = || format!("bad value for {}: '{}'", key, value);
+            match key {
+                "starting_player" => {
+                    rules.starting_player = match value {
+                        "A" => Player::A,
+                        "B" => Player::B,
+                        _ => return Err(bad()),
+                    }
+                }
+                "play_move_timeout" => {
+                    rules.play_move_timeout = value.parse().map_err(|_| bad())?
+                }
+                "time_control" => rules.time_control = TimeControl::parse(value)?,
+                "flag_fall_loses" => rules.flag_fall_loses = value.parse().map_err(|_| bad())?,
+                "dasher_can_fly" => rules.dasher_can_fly = value.parse().map_err(|_| bad())?,
+                "falling_tiles" => rules.falling_tiles = value.parse().map_err(|_| bad())?,
+                "falling_tiles_heads_up" => {
+                    rules.falling_tiles_heads_up = value.parse().map_err(|_| bad())?
+                }
+                "bomber_fuse" => rules.bomber_fuse = value.parse().map_err(|_| bad())?,
+                "lava_turns" => rules.lava_turns = value.parse().map_err(|_| bad())?,
+                "regrow_turns" => rules.regrow_turns = value.parse().map_err(|_| bad())?,
+                "repetitions" => rules.repetitions = value.parse().map_err(|_| bad())?,
+                "no_progress_limit" => {
+                    rules.no_progress_limit = value.parse().map_err(|_| bad())?
+                }
+                _ => return Err(format!("unknown rule: '{}'", key)),
+            }
+        }
+        if rules.play_move_timeout <= 0.0 {
+            return Err("play_move_timeout must be positive".to_string());
+        }
+        if rules.bomber_fuse == 0 || rules.lava_turns == 0 || rules.regrow_turns == 0 {
+            return Err("bomber_fuse, lava_turns and regrow_turns must be at least 1".to_string());
+        }
+        if rules.repetitions == 1 {
+            return Err("repetitions must be 0 (never) or at least 2".to_string());
+        }
+        Ok(rules)
+    }
+}
+
+/// the name of the preset, and every rule spelled out (presets depend on the config)
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        write!(f, " starting_player={:?}", self.starting_player)?;
+        write!(f, " play_move_timeout={}", self.play_move_timeout)?;
+        write!(f, " time_control={}", self.time_control)?;
+        write!(f, " flag_fall_loses={}", self.flag_fall_loses)?;
+        write!(f, " dasher_can_fly={}", self.dasher_can_fly)?;
+        write!(f, " falling_tiles={}", self.falling_tiles)?;
+        write!(f, " falling_tiles_heads_up={}", self.falling_tiles_heads_up)?;
+        write!(f, " bomber_fuse={}", self.bomber_fuse)?;
+        write!(f, " lava_turns={}", self.lava_turns)?;
+        write!(f, " regrow_turns={}", self.regrow_turns)?;
+        write!(f, " repetitions={}", self.repetitions)?;
+        write!(f, " no_progress_limit={}", self.no_progress_limit)
+    }
+}
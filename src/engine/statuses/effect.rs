@@ -4,7 +4,7 @@ use crate::engine::{
     Object,
 };
 use crate::geometry::{HexCoord, ScreenCoord};
-use macroquad::prelude::*;
+use glam::vec3;
 
 /**
 `Effect`s are sent by other entities to the `GameController` for it to apply.
@@ -22,6 +22,32 @@ pub enum Effect {
         apply: Option<Box<StatusType>>,
         duration: Option<f32>,
     },
+    /// shoves `object`, and every piece lined up behind it, one step in `direction`
+    Push {
+        object: Object,
+        direction: usize,
+        /// at what stage of the move-animation the push occurs
+        animation_delay_frac: Option<f32>,
+    },
+    /// lights the fuse of a Bomber, which then blows up `fuse` moves later
+    Arm {
+        object: Object,
+        fuse: usize,
+    },
+    /// kills every piece on and next to the tile `bomber` stands on
+    Detonate {
+        bomber: Object,
+    },
+    /// sends a piece that stopped on a teleporter to `to`
+    Teleport {
+        object: Object,
+        to: HexCoord,
+    },
+    /// puts a fallen Regrowing tile back, to fall again on move `lifespan`
+    Regrow {
+        tile: Object,
+        lifespan: usize,
+    },
     SetStatus {
         object: Object,
         stype: Box<StatusType>,
@@ -33,6 +59,10 @@ pub enum Effect {
 impl Effect {
     /**
     returns any Status that should be applied to the things the effect will apply to
+
+    a `Push` has none: each piece it moves gets its own (see `rules::apply_effects`).
+    neither do `Arm`, `Detonate`, `Teleport` and `Regrow`, which are worked out on
+    the board as well.
     */
     pub fn applying_status(&self, time: f32) -> Option<Status> {
         match self {
@@ -48,7 +78,7 @@ impl Effect {
                 } else {
                     vec3(0.0, 0.0, 0.0)
                 };
-                let duration = CONF.kill_duration;
+                let duration = CONF.get().kill_duration;
                 let start_time = time + duration * animation_delay_frac.unwrap_or(0.0);
                 let status = Status::new_killed(knockback, start_time, duration);
                 Some(status)
@@ -67,6 +97,11 @@ impl Effect {
                     None
                 }
             }
+            Effect::Push { .. }
+            | Effect::Arm { .. }
+            | Effect::Detonate { .. }
+            | Effect::Teleport { .. }
+            | Effect::Regrow { .. } => None,
             Effect::SetStatus {
                 stype, duration, ..
             } => {
@@ -1,6 +1,6 @@
 use crate::engine::statuses::{Effect, StatusType};
 use crate::geometry::ScreenCoord;
-use macroquad::prelude::*;
+use glam::Vec3;
 
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct Status {
@@ -32,6 +32,14 @@ impl Status {
         }
     }
 
+    pub fn new_pushed(from: Vec3, to: Vec3, start_time: f32, duration: f32) -> Self {
+        Status {
+            stype: StatusType::Pushed { from, to },
+            start_time: Some(start_time),
+            duration: Some(duration),
+        }
+    }
+
     pub fn new_killed(knockback: Vec3, start_time: f32, duration: f32) -> Self {
         Status {
             stype: StatusType::Killed { knockback },
@@ -51,7 +59,7 @@ impl Status {
     pub fn new_delayed_effect(move_nr: usize, effect: Effect) -> Status {
         Status::new(StatusType::DelayedEffect {
             move_nr,
-            effect,
+            effect: Box::new(effect),
             indicator_move_nr: None,
             indicator: None,
         })
@@ -65,9 +73,9 @@ impl Status {
     ) -> Status {
         Status::new(StatusType::DelayedEffect {
             move_nr,
-            effect,
+            effect: Box::new(effect),
             indicator_move_nr: Some(indicator_move_nr),
-            indicator: Some(indicator),
+            indicator: Some(Box::new(indicator)),
         })
     }
 
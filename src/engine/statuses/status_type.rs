@@ -1,6 +1,6 @@
 use crate::engine::statuses::Effect;
 use crate::geometry::ScreenCoord;
-use macroquad::prelude::*;
+use glam::Vec3;
 
 #[derive(Clone, Debug, PartialEq, Default)]
 pub enum StatusType {
@@ -17,15 +17,24 @@ pub enum StatusType {
         to: ScreenCoord,
         height: f32,
     },
+    /// shoved by a Pusher; `from` and `to` are offsets from the object's coord
+    Pushed {
+        from: Vec3,
+        to: Vec3,
+    },
     Wobble {
         amplitude: f32,
         speed: f32,
     },
     Falling,
+    /// standing on lava since `move_nr`
+    Burning {
+        move_nr: usize,
+    },
     DelayedEffect {
         move_nr: usize,
-        effect: Effect,
+        effect: Box<Effect>,
         indicator_move_nr: Option<usize>,
-        indicator: Option<Effect>,
+        indicator: Option<Box<Effect>>,
     },
 }
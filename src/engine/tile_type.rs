@@ -1,4 +1,16 @@
-#[derive(Debug, Copy, Clone)]
+/**
+What a tile does to the pieces on it, besides holding them up.
+*/
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
 pub enum TileType {
+    #[default]
     Ground,
+    /// a Dasher coming off ice slides on over one gap, if there is a tile behind it
+    Ice,
+    /// kills a piece that has stood on it for `RuleSet::lava_turns` moves
+    Lava,
+    /// a piece that stops here is sent to the other teleporter of the same `channel`
+    Teleporter { channel: u8 },
+    /// comes back `RuleSet::regrow_turns` moves after falling, and lives as long again
+    Regrowing,
 }
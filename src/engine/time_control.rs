@@ -0,0 +1,210 @@
+/*!
+Time controls, as on a chess clock.
+
+Under every control but `TimeControl::PerMove`, each player has a bank of
+time that runs down while it is their turn. How the bank is topped up after a
+move is what tells the controls apart. A player whose bank runs out has their
+flag fall: they either lose (`RuleSet::flag_fall_loses`), or play on with
+`RuleSet::play_move_timeout` for each move, as under `PerMove`.
+
+All times are in seconds.
+*/
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeControl {
+    /// no bank; every move may take `RuleSet::play_move_timeout`
+    PerMove,
+    /// `main` for the whole game
+    SuddenDeath { main: f32 },
+    /// `main`, and `increment` more after every move
+    Fischer { main: f32, increment: f32 },
+    /// `main`; after every move, the time it took is given back, up to `delay`
+    Bronstein { main: f32, delay: f32 },
+    /**
+    `main`, and then `periods` periods of `period` each. a move made within a
+    period keeps it for the next move; running over a period uses it up.
+    */
+    ByoYomi {
+        main: f32,
+        periods: usize,
+        period: f32,
+    },
+}
+
+/// what is left on one player's clock between their moves
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeBank {
+    pub main: f32,
+    /// byo-yomi periods not used up yet
+    pub periods: usize,
+    /// the bank ran out, and the player is on `RuleSet::play_move_timeout` per move
+    pub flagged: bool,
+}
+
+impl TimeControl {
+    /// a full bank, as each player starts with
+    pub fn start(&self) -> TimeBank {
+        let (main, periods) = match *self {
+            TimeControl::PerMove => (0.0, 0),
+            TimeControl::SuddenDeath { main }
+            | TimeControl::Fischer { main, .. }
+            | TimeControl::Bronstein { main, .. } => (main, 0),
+            TimeControl::ByoYomi { main, periods, .. } => (main, periods),
+        };
+        TimeBank {
+            main,
+            periods,
+            flagged: false,
+        }
+    }
+
+    /**
+    how long until the flag falls, for a player with `bank` who has spent
+    `elapsed` on the current move. `per_move` is `RuleSet::play_move_timeout`.
+    */
+    pub fn time_left(&self, bank: &TimeBank, elapsed: f32, per_move: f32) -> f32 {
+        if bank.flagged {
+            return per_move - elapsed;
+        }
+        match *self {
+            TimeControl::PerMove => per_move - elapsed,
+            TimeControl::ByoYomi { period, .. } => {
+                bank.main + bank.periods as f32 * period - elapsed
+            }
+            _ => bank.main - elapsed,
+        }
+    }
+
+    /// the bank after a move that took `elapsed`; flagged if that used up all that was left
+    pub fn charge(&self, bank: TimeBank, elapsed: f32, per_move: f32) -> TimeBank {
+        if bank.flagged || *self == TimeControl::PerMove {
+            return bank;
+        }
+        if self.time_left(&bank, elapsed, per_move) <= 0.0 {
+            return TimeBank {
+                main: 0.0,
+                periods: 0,
+                flagged: true,
+            };
+        }
+        let main = bank.main - elapsed;
+        match *self {
+            TimeControl::PerMove => bank,
+            TimeControl::SuddenDeath { .. } => TimeBank { main, ..bank },
+            TimeControl::Fischer { increment, .. } => TimeBank {
+                main: main + increment,
+                ..bank
+            },
+            TimeControl::Bronstein { delay, .. } => TimeBank {
+                main: main + elapsed.min(delay),
+                ..bank
+            },
+            TimeControl::ByoYomi { period, .. } if main < 0.0 => {
+                let used = (-main / period) as usize;
+                TimeBank {
+                    main: 0.0,
+                    periods: bank.periods.saturating_sub(used),
+                    ..bank
+                }
+            }
+            TimeControl::ByoYomi { .. } => TimeBank { main, ..bank },
+        }
+    }
+
+    /**
+    reads a time control written by `Display`: `per-move`,
+    `sudden-death:<main>`, `fischer:<main>+<increment>`,
+    `bronstein:<main>+<delay>` or `byo-yomi:<main>+<periods>x<period>`.
+    */
+    pub fn parse(text: &str) -> Result<TimeControl, String> {
+        let bad = || format!("bad time control: '{}'", text);
+        let seconds = |s: &str| match s.parse::<f32>() {
+            Ok(t) if t >= 0.0 && t.is_finite() => Ok(t),
+            _ => Err(bad()),
+        };
+        let (kind, args) = text.split_once(':').unwrap_or((text, ""));
+        let (main, extra) = args.split_once('+').unwrap_or((args, ""));
+        let control = match (kind, extra) {
+            ("per-move", _) if args.is_empty() => TimeControl::PerMove,
+            ("sudden-death", "") => TimeControl::SuddenDeath {
+                main: seconds(main)?,
+            },
+            ("fischer", increment) => TimeControl::Fischer {
+                main: seconds(main)?,
+                increment: seconds(increment)?,
+            },
+            ("bronstein", delay) => TimeControl::Bronstein {
+                main: seconds(main)?,
+                delay: seconds(delay)?,
+            },
+            ("byo-yomi", extra) => {
+                let (periods, period) = extra.split_once('x').ok_or_else(bad)?;
+                TimeControl::ByoYomi {
+                    main: seconds(main)?,
+                    periods: periods.parse().map_err(|_| bad())?,
+                    period: seconds(period)?,
+                }
+            }
+            _ => return Err(bad()),
+        };
+        if let TimeControl::ByoYomi { period, .. } = control {
+            if period <= 0.0 {
+                return Err("byo-yomi periods must be longer than 0".to_string());
+            }
+        }
+        let bank = control.start();
+        if control != TimeControl::PerMove && bank.main <= 0.0 && bank.periods == 0 {
+            return Err("there must be some time on the clock".to_string());
+        }
+        Ok(control)
+    }
+}
+
+/// `per-move`, `fischer:300+5`, `byo-yomi:600+5x30`, ...
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeControl::PerMove => write!(f, "per-move"),
+            TimeControl::SuddenDeath { main } => write!(f, "sudden-death:{}", main),
+            TimeControl::Fischer { main, increment } => write!(f, "fischer:{}+{}", main, increment),
+            TimeControl::Bronstein { main, delay } => write!(f, "bronstein:{}+{}", main, delay),
+            TimeControl::ByoYomi {
+                main,
+                periods,
+                period,
+            } => write!(f, "byo-yomi:{}+{}x{}", main, periods, period),
+        }
+    }
+}
+
+impl TimeBank {
+    /// reads a bank written by `Display`: `<main>`, `<main>+<periods>` or `flagged`
+    pub fn parse(text: &str) -> Result<TimeBank, String> {
+        let bad = || format!("bad time bank: '{}'", text);
+        if text == "flagged" {
+            return Ok(TimeBank {
+                main: 0.0,
+                periods: 0,
+                flagged: true,
+            });
+        }
+        let (main, periods) = text.split_once('+').unwrap_or((text, "0"));
+        Ok(TimeBank {
+            main: main.parse().map_err(|_| bad())?,
+            periods: periods.parse().map_err(|_| bad())?,
+            flagged: false,
+        })
+    }
+}
+
+/// `283.4107`, `0+3`, `flagged`, ...; the time is written in full, so it reads back the same
+impl fmt::Display for TimeBank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.flagged, self.periods) {
+            (true, _) => write!(f, "flagged"),
+            (false, 0) => write!(f, "{}", self.main),
+            (false, periods) => write!(f, "{}+{}", self.main, periods),
+        }
+    }
+}
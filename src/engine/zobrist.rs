@@ -0,0 +1,166 @@
+/*!
+Zobrist hashing of board positions.
+
+Every feature of a position (a piece of some type and owner on a coord, a
+tile of some type, an effect some object has lined up) gets a random key, and
+the hash of a board is the sum of the keys of its features. Each object adds
+its own features, so when an object changes, only its part of the sum has to
+be made again.
+
+Effects lined up for later count by how many moves away they are, rather than
+by the move they are due on, so the same position hashes the same on any move.
+This works as their keys are summed times `G^due`: multiplying the sum by
+`G^-move_nr` turns every `due` into `due - move_nr` at once.
+*/
+use crate::engine::statuses::{Effect, StatusType};
+use crate::engine::{Object, Player, TileType};
+use crate::rng::Rng;
+use std::ops::{Add, Sub};
+
+/// odd, so it has an inverse; any odd number with its bits well mixed does
+const G: u64 = 0x9e37_79b9_7f4a_7c15;
+const G_INV: u64 = inverse(G);
+
+/// the inverse of an odd `g`, modulo 2^64, by Newton's method
+const fn inverse(g: u64) -> u64 {
+    let mut inv = g;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(g.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv
+}
+
+enum Feature {
+    Piece = 1,
+    Tile,
+    Pending,
+    Burning,
+    ToMove,
+}
+
+/// the random key for a feature, and whatever tells it apart from others of its kind
+fn key(feature: Feature, parts: &[u64]) -> u64 {
+    let start = Rng::new(feature as u64).next_u64();
+    parts.iter().fold(start, |h, p| Rng::new(h ^ p).next_u64())
+}
+
+fn tile_type_nr(tile_type: TileType) -> u64 {
+    match tile_type {
+        TileType::Ground => 0,
+        TileType::Ice => 1,
+        TileType::Lava => 2,
+        TileType::Teleporter { channel } => 3 + channel as u64,
+        TileType::Regrowing => 3 + 256,
+    }
+}
+
+/// what tells lined-up effects apart, besides the object holding them and when they are due
+fn effect_parts(effect: &Effect, due: usize) -> [u64; 2] {
+    match effect {
+        Effect::Kill { .. } => [1, 0],
+        Effect::KillAallOn { .. } => [2, 0],
+        Effect::Push { direction, .. } => [3, *direction as u64],
+        Effect::Arm { fuse, .. } => [4, *fuse as u64],
+        Effect::Detonate { .. } => [5, 0],
+        Effect::Teleport { .. } => [6, 0],
+        Effect::Regrow { lifespan, .. } => [7, lifespan.wrapping_sub(due) as u64],
+        Effect::SetStatus { .. } => [8, 0],
+        Effect::NoOp => [9, 0],
+    }
+}
+
+/**
+A part of the hash of a board, or all of it, before it is known which move
+it is and who is to make it.
+
+Effects lined up on tiles are kept apart from the rest, since they only
+count when tiles fall.
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Zobrist {
+    fixed: u64,
+    pending_on_tiles: u64,
+    pending: u64,
+}
+
+impl Zobrist {
+    /// what `object` adds to the hash of the board it is on
+    pub fn of(object: &Object) -> Zobrist {
+        let mut zobrist = Zobrist::default();
+        let (x, y) = (object.coord.x as u64, object.coord.y as u64);
+        if !object.props.dead {
+            zobrist.fixed = if object.is_tile() {
+                key(Feature::Tile, &[tile_type_nr(object.tile_type()), x, y])
+            } else {
+                let (otype, owner) = (object.otype as u64, object.player as u64);
+                key(Feature::Piece, &[otype, owner, x, y])
+            };
+        }
+        let mut pending = 0u64;
+        for status in &object.statuses {
+            let (k, due) = match &status.stype {
+                StatusType::DelayedEffect {
+                    move_nr, effect, ..
+                } => {
+                    let [kind, arg] = effect_parts(effect, *move_nr);
+                    let holder = object.is_tile() as u64;
+                    (key(Feature::Pending, &[holder, kind, arg, x, y]), *move_nr)
+                }
+                // the dead don't burn any more
+                StatusType::Burning { move_nr } if !object.props.dead => {
+                    (key(Feature::Burning, &[x, y]), *move_nr)
+                }
+                _ => continue,
+            };
+            pending = pending.wrapping_add(k.wrapping_mul(G.wrapping_pow(due as u32)));
+        }
+        if object.is_tile() {
+            zobrist.pending_on_tiles = pending;
+        } else {
+            zobrist.pending = pending;
+        }
+        zobrist
+    }
+
+    /**
+    the hash on move `move_nr`, with `to_move` to move. effects lined up on
+    tiles are left out unless `falling_tiles`, as tiles don't do anything then.
+    */
+    pub fn hash(&self, move_nr: usize, to_move: Player, falling_tiles: bool) -> u64 {
+        let mut pending = self.pending;
+        if falling_tiles {
+            pending = pending.wrapping_add(self.pending_on_tiles);
+        }
+        let side = match to_move {
+            Player::B => key(Feature::ToMove, &[]),
+            _ => 0,
+        };
+        self.fixed
+            .wrapping_add(pending.wrapping_mul(G_INV.wrapping_pow(move_nr as u32)))
+            .wrapping_add(side)
+    }
+}
+
+impl Add for Zobrist {
+    type Output = Zobrist;
+    fn add(self, other: Zobrist) -> Zobrist {
+        Zobrist {
+            fixed: self.fixed.wrapping_add(other.fixed),
+            pending_on_tiles: self.pending_on_tiles.wrapping_add(other.pending_on_tiles),
+            pending: self.pending.wrapping_add(other.pending),
+        }
+    }
+}
+
+impl Sub for Zobrist {
+    type Output = Zobrist;
+    fn sub(self, other: Zobrist) -> Zobrist {
+        Zobrist {
+            fixed: self.fixed.wrapping_sub(other.fixed),
+            pending_on_tiles: self.pending_on_tiles.wrapping_sub(other.pending_on_tiles),
+            pending: self.pending.wrapping_sub(other.pending),
+        }
+    }
+}
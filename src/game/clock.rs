@@ -0,0 +1,63 @@
+use std::time::Instant;
+
+/**
+Source of time for the `GameController`.
+
+The rules never look at the wall-clock themselves; whoever drives the game
+(the UI, a test, a bot, a server) decides how time passes by supplying a clock.
+All times are in seconds.
+*/
+pub trait Clock {
+    fn now(&self) -> f32;
+}
+
+/// Real time, counted from when the clock was created.
+pub struct WallClock {
+    start: Instant,
+}
+
+impl Default for WallClock {
+    fn default() -> Self {
+        WallClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl WallClock {
+    pub fn new() -> WallClock {
+        WallClock::default()
+    }
+}
+
+impl Clock for WallClock {
+    fn now(&self) -> f32 {
+        self.start.elapsed().as_secs_f32()
+    }
+}
+
+/// Logical time that only moves when told to; for headless games.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ManualClock {
+    time: f32,
+}
+
+impl ManualClock {
+    pub fn new(time: f32) -> ManualClock {
+        ManualClock { time }
+    }
+
+    pub fn set(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.time += dt;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> f32 {
+        self.time
+    }
+}
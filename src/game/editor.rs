@@ -0,0 +1,140 @@
+use crate::engine::{Board, Object, ObjectType, Player, RuleSet};
+use crate::geometry::HexCoord;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditTool {
+    /// add a tile, or remove it (and whatever is on it)
+    Tile,
+    Wall,
+    /// pieces are placed for `Editor::player`
+    Dasher,
+    Jumper,
+    Pusher,
+    Bomber,
+    /// set the lifespan of a tile to the brush value
+    Lifespan,
+}
+
+impl EditTool {
+    pub const ALL: [EditTool; 7] = [
+        EditTool::Tile,
+        EditTool::Wall,
+        EditTool::Dasher,
+        EditTool::Jumper,
+        EditTool::Pusher,
+        EditTool::Bomber,
+        EditTool::Lifespan,
+    ];
+}
+
+/**
+State of the map editor, used in `GameState::Editing`.
+*/
+#[derive(Debug, Clone)]
+pub struct Editor {
+    pub tool: EditTool,
+    /// owner of the pieces placed by the piece tools
+    pub player: Player,
+    /// lifespan given to new tiles, and painted by `EditTool::Lifespan`
+    pub lifespan: usize,
+    /// feedback for the user, e.g. why the board can't be played
+    pub message: Option<String>,
+}
+
+impl Default for Editor {
+    fn default() -> Editor {
+        Editor {
+            tool: EditTool::Tile,
+            player: Player::A,
+            lifespan: 40,
+            message: None,
+        }
+    }
+}
+
+impl Editor {
+    pub fn swap_player(&mut self) {
+        self.player = self.player.opponent();
+    }
+
+    pub fn lifespan_up(&mut self) {
+        self.lifespan += 1;
+    }
+
+    pub fn lifespan_down(&mut self) {
+        self.lifespan = self.lifespan.saturating_sub(1).max(1);
+    }
+
+    /**
+    uses the current tool on `coord`.
+    placing a piece where the same kind of piece is already standing removes it.
+    */
+    pub fn apply(&self, board: &mut Board, coord: &HexCoord, rules: &RuleSet) {
+        match self.tool {
+            EditTool::Tile => match board.tile_at(coord).cloned() {
+                Some(_) => Editor::clear(board, coord),
+                None => {
+                    let oid = board.next_oid();
+                    board.add_object(Object::new_tile(oid, *coord, self.lifespan, rules));
+                }
+            },
+            EditTool::Lifespan => {
+                if let Some(tile) = board.tile_at(coord).cloned() {
+                    board.remove_object(&tile);
+                    let oid = tile.props.oid;
+                    let new = Object::new_tile(oid, *coord, self.lifespan, rules);
+                    board.add_object(new.with_tile_type(tile.tile_type()));
+                }
+            }
+            EditTool::Wall => Editor::toggle_piece(board, coord, ObjectType::Wall, Player::God),
+            EditTool::Dasher => self.place(board, coord, ObjectType::Dasher),
+            EditTool::Jumper => self.place(board, coord, ObjectType::Jumper),
+            EditTool::Pusher => self.place(board, coord, ObjectType::Pusher),
+            EditTool::Bomber => self.place(board, coord, ObjectType::Bomber),
+        }
+    }
+
+    /**
+    removes the piece on `coord`, or the tile if there is no piece.
+    */
+    pub fn erase(&self, board: &mut Board, coord: &HexCoord) {
+        match board.piece_at(coord).cloned() {
+            Some(piece) => board.remove_object(&piece),
+            None => Editor::clear(board, coord),
+        }
+    }
+
+    fn place(&self, board: &mut Board, coord: &HexCoord, otype: ObjectType) {
+        Editor::toggle_piece(board, coord, otype, self.player)
+    }
+
+    fn toggle_piece(board: &mut Board, coord: &HexCoord, otype: ObjectType, player: Player) {
+        if board.tile_at(coord).is_none() {
+            return;
+        }
+        let existing = board.piece_at(coord).cloned();
+        if let Some(piece) = &existing {
+            board.remove_object(piece);
+        }
+        if existing.is_some_and(|p| p.otype == otype && p.player == player) {
+            return;
+        }
+        let oid = board.next_oid();
+        let piece = match otype {
+            ObjectType::Wall => Object::new_wall(oid, *coord),
+            _ => Object::new(oid, otype, *coord, player),
+        };
+        board.add_object(piece);
+    }
+
+    fn clear(board: &mut Board, coord: &HexCoord) {
+        let on_coord: Vec<Object> = board
+            .objects()
+            .into_iter()
+            .filter(|o| &o.coord == coord)
+            .cloned()
+            .collect();
+        on_coord.iter().for_each(|o| board.remove_object(o));
+        board.reshape();
+    }
+}
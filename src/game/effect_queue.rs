@@ -0,0 +1,194 @@
+/*!
+Resolving effects until nothing new comes up.
+
+Applying an effect can cause more of them: a Bomber that is killed
+detonates, which kills its neighbours, which may be Bombers too. The
+`EffectQueue` keeps resolving until it is empty, and writes down everything
+that happened in an `EffectLog`.
+
+Ordering is breadth first: the effects that were queued up front resolve in
+the order they were given, then everything those caused (in the order it was
+caused), then everything *that* caused, and so on.
+*/
+use crate::engine::{statuses::Effect, Object, Player};
+use crate::geometry::HexCoord;
+use std::collections::{HashMap, VecDeque};
+use std::mem::{self, Discriminant};
+
+/// no turn needs anywhere near this many; past it, the queue gives up
+pub const MAX_EFFECTS: usize = 4096;
+
+/// where an effect in the log came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    /// came with the move that was played
+    Move,
+    /// triggered by an object on the board (e.g. a `DelayedEffect`)
+    Object,
+    /// caused by resolving the effect at this index of the log
+    Effect(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedEffect {
+    pub effect: Effect,
+    pub origin: Origin,
+    /// 0 for effects that were queued up front, one more than their cause otherwise
+    pub depth: usize,
+}
+
+/**
+Everything that resolved during one turn, in the order it resolved.
+*/
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectLog {
+    pub entries: Vec<LoggedEffect>,
+    /// effects that were not resolved, since an equal one already had been
+    pub skipped: Vec<Effect>,
+    /// the queue hit `MAX_EFFECTS`, and the rest was dropped
+    pub truncated: bool,
+    /// when the effects were resolved
+    pub time: f32,
+}
+
+impl EffectLog {
+    pub fn effects(&self) -> impl Iterator<Item = &Effect> {
+        self.entries.iter().map(|e| &e.effect)
+    }
+
+    pub fn from_origin(&self, origin: Origin) -> impl Iterator<Item = &Effect> {
+        self.entries
+            .iter()
+            .filter(move |e| e.origin == origin)
+            .map(|e| &e.effect)
+    }
+
+    /// every piece killed outright (not by falling), in the order they died
+    pub fn victims(&self) -> impl Iterator<Item = &Object> {
+        self.effects().filter_map(|e| match e {
+            Effect::Kill { victim, .. } => Some(victim),
+            _ => None,
+        })
+    }
+
+    /// how many of `player`'s pieces were killed outright
+    pub fn losses(&self, player: &Player) -> usize {
+        self.victims().filter(|v| v.owned_by(player)).count()
+    }
+
+    /// the tiles hit by a detonation: where a Bomber stood, and around it
+    pub fn blasted(&self) -> Vec<HexCoord> {
+        self.effects()
+            .filter_map(|e| match e {
+                Effect::Detonate { bomber } => Some(bomber.coord),
+                _ => None,
+            })
+            .flat_map(|c| std::iter::once(c).chain(c.get_all_neighbours(1)))
+            .collect()
+    }
+
+    /// the deepest chain of effects causing each other
+    pub fn depth(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|e| e.depth)
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EffectQueue {
+    queue: VecDeque<LoggedEffect>,
+}
+
+impl EffectQueue {
+    pub fn new() -> EffectQueue {
+        EffectQueue::default()
+    }
+
+    pub fn push(&mut self, effect: Effect, origin: Origin) {
+        self.queue.push_back(LoggedEffect {
+            effect,
+            origin,
+            depth: 0,
+        });
+    }
+
+    pub fn extend(&mut self, effects: impl IntoIterator<Item = Effect>, origin: Origin) {
+        effects.into_iter().for_each(|e| self.push(e, origin));
+    }
+
+    /**
+    resolves effects with `apply` until the queue is empty. `apply` returns
+    the effects that resolving one caused, which are queued behind the rest.
+
+    an effect equal to one that already resolved is skipped, so effects that
+    cause each other in a cycle stop after one round.
+    */
+    pub fn resolve(
+        &mut self,
+        time: f32,
+        mut apply: impl FnMut(&Effect) -> Vec<Effect>,
+    ) -> EffectLog {
+        let mut log = EffectLog {
+            time,
+            ..Default::default()
+        };
+        // indices into the log, of the resolved effects with each key
+        let mut resolved: HashMap<EffectKey, Vec<usize>> = HashMap::new();
+        while let Some(next) = self.queue.pop_front() {
+            if log.len() == MAX_EFFECTS {
+                log.truncated = true;
+                self.queue.clear();
+                break;
+            }
+            let same_key = resolved.entry(key(&next.effect)).or_default();
+            if same_key
+                .iter()
+                .any(|&i| log.entries[i].effect == next.effect)
+            {
+                log.skipped.push(next.effect);
+                continue;
+            }
+            let index = log.len();
+            same_key.push(index);
+            let caused = apply(&next.effect);
+            let depth = next.depth + 1;
+            self.queue
+                .extend(caused.into_iter().map(|effect| LoggedEffect {
+                    effect,
+                    origin: Origin::Effect(index),
+                    depth,
+                }));
+            log.entries.push(next);
+        }
+        log
+    }
+}
+
+/// what an effect is, and what it acts on; equal effects have equal keys
+type EffectKey = (Discriminant<Effect>, Option<usize>, Option<HexCoord>);
+
+fn key(effect: &Effect) -> EffectKey {
+    let (oid, coord) = match effect {
+        Effect::Kill { victim: o, .. }
+        | Effect::Push { object: o, .. }
+        | Effect::Arm { object: o, .. }
+        | Effect::Detonate { bomber: o }
+        | Effect::Regrow { tile: o, .. }
+        | Effect::SetStatus { object: o, .. } => (Some(o.props.oid), Some(o.coord)),
+        Effect::Teleport { object, to } => (Some(object.props.oid), Some(*to)),
+        Effect::KillAallOn { coord, .. } => (None, Some(*coord)),
+        Effect::NoOp => (None, None),
+    };
+    (mem::discriminant(effect), oid, coord)
+}
@@ -1,26 +1,74 @@
+use crate::ai::{Bot, Position};
 use crate::config::CONF;
-use crate::engine::{
-    statuses::{Effect, Status},
-    Board, Object, ObjectType, Player,
+use crate::engine::{Board, BoardError, Object, Player, RuleSet, TimeBank};
+use crate::game::{
+    editor::Editor,
+    effect_queue::EffectLog,
+    history::{History, Snapshot, TurnRecord},
+    moves::Move,
+    notation::{GameRecord, NotationError, RecordedTurn},
+    replay::Replay,
+    rules, Clock, GameOutcome, Reason,
 };
-use crate::game::moves::Move;
-use crate::geometry::{HexCoord, ScreenCoord};
+use crate::geometry::ScreenCoord;
+use crate::rng::Rng;
 use itertools::Itertools;
-use macroquad::prelude::*;
-use std::time::Instant;
+use std::collections::HashMap;
+use std::thread::{self, JoinHandle};
 
 use super::GameState;
 
 pub struct GameController {
     pub board: Board,
+    pub rules: RuleSet,
     pub game_state: GameState,
+    /// what is left on each player's clock, as of the start of the current move
+    pub clocks: HashMap<Player, TimeBank>,
+    pub history: History,
+    pub replay: Option<Replay>,
+    pub editor: Option<Editor>,
+    /// players whose moves are chosen by the computer
+    pub bots: HashMap<Player, Box<dyn Bot>>,
+    /**
+    let bots search on a thread of their own, and pick up their move in a
+    later `tick`, so a long search doesn't freeze the game. off by default,
+    so headless games play their bots' moves right away.
+    */
+    pub bots_in_background: bool,
+    /// a bot searching in the background; it is out of `bots` until it is done
+    search: Option<Search>,
+    /// turns are decided by a server, so there are no local timeouts
+    pub networked: bool,
+    /**
+    what `rng` started from. it is written into game records, so a game
+    replays the same from it, bots and all (as long as they don't search
+    for a given time; `Mcts` does so only without `Mcts::iterations`).
+    */
+    pub seed: u64,
+    /// the one source of randomness for anything random in the game
+    pub rng: Rng,
+    /// turns in a row on which no piece died, for `RuleSet::no_progress_limit`
+    pub moves_since_death: usize,
 }
 
 impl Default for GameController {
     fn default() -> Self {
+        let seed = Rng::from_time().next_u64();
         GameController {
             board: Board::test_square(),
+            rules: RuleSet::default(),
             game_state: GameState::Waiting,
+            clocks: full_clocks(&RuleSet::default()),
+            history: History::default(),
+            replay: None,
+            editor: None,
+            bots: HashMap::new(),
+            bots_in_background: false,
+            search: None,
+            networked: false,
+            seed,
+            rng: Rng::new(seed),
+            moves_since_death: 0,
         }
     }
 }
@@ -33,44 +81,354 @@ impl GameController {
         }
     }
 
-    pub fn start_game(&mut self) {
+    pub fn with_board(board: Board) -> GameController {
+        GameController {
+            board,
+            ..Default::default()
+        }
+    }
+
+    /**
+    a game that plays itself out from a record, in `GameState::Replay`.
+
+    the whole record is checked up front, so a replay should never stop
+    halfway; if it does go out of sync, it stops with `Replay::error` set.
+    */
+    pub fn new_replay(
+        record: GameRecord,
+        clock: &impl Clock,
+    ) -> Result<GameController, NotationError> {
+        record.replay()?;
+        let time = clock.now();
+        let rules = record.rules()?;
+        let mut board = record.board()?;
+        board.apply_rules(&rules);
+        let seed = record.seed()?;
+        Ok(GameController {
+            rules,
+            game_state: GameState::Replay {
+                current_player: rules.starting_player,
+                move_start: time,
+                move_nr: 0,
+            },
+            clocks: full_clocks(&rules),
+            replay: Some(Replay::new(record.clone(), time)),
+            seed: seed.unwrap_or_default(),
+            rng: Rng::new(seed.unwrap_or_default()),
+            ..GameController::with_board(board)
+        })
+    }
+
+    /**
+    the game so far, to carry on with later (see `resume`): its record, with
+    the clocks as they stand, how long the current move has been going on,
+    and where its randomness is at. the times are written in full.
+    */
+    pub fn save(&self, clock: &impl Clock) -> GameRecord {
+        let mut record = GameRecord::from_controller(self);
+        if let Some(move_start) = self.game_state.move_start() {
+            let move_time = (clock.now() - move_start).max(0.0);
+            record.set_header("MoveTime", &move_time.to_string());
+        }
+        let clocks = [Player::A, Player::B].map(|p| self.clocks[&p].to_string());
+        record.set_header("Clocks", &clocks.join(" "));
+        record.set_header("RngState", &self.rng.state().to_string());
+        record
+    }
+
+    /**
+    carries on with a game from `save`, as if it had never stopped.
+
+    the turns are played again, rather than the board being read back: the
+    board holds more than a map can say (when each tile falls, who is burning
+    on lava, which tiles are to regrow, which bombs are ticking), and playing
+    the turns again builds all of it up by the same rules, along with the
+    history to undo through. what the turns don't tell exactly is put back
+    from the headers: the clocks (turns only have their think time to the
+    hundredth), when the current move started, and the randomness.
+
+    bots are not saved, and have to be set again.
+    */
+    pub fn resume(
+        record: &GameRecord,
+        clock: &impl Clock,
+    ) -> Result<GameController, NotationError> {
+        let move_time: f32 = match record.header("MoveTime") {
+            Some(t) => t
+                .parse()
+                .map_err(|_| NotationError::Syntax(format!("MoveTime is not a number: {}", t)))?,
+            None => 0.0,
+        };
+        let start = clock.now() - move_time - record.duration();
+        let mut game = record.replay_from(start)?;
+        game.game_state = game.game_state.restarted_at(clock.now() - move_time);
+        if let Some(clocks) = record.header("Clocks") {
+            let bad = || NotationError::Syntax(format!("bad Clocks: {}", clocks));
+            let banks: Vec<TimeBank> = clocks
+                .split_whitespace()
+                .map(TimeBank::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|_| bad())?;
+            let [a, b] = banks[..] else {
+                return Err(bad());
+            };
+            game.clocks = HashMap::from([(Player::A, a), (Player::B, b)]);
+        }
+        if let Some(state) = record.header("RngState") {
+            let state = state.parse().map_err(|_| {
+                NotationError::Syntax(format!("RngState is not a number: {}", state))
+            })?;
+            game.rng = Rng::new(state);
+        }
+        let ended = record.header("Termination").and_then(GameOutcome::parse);
+        if let (Some(outcome), None) = (ended, game.game_state.outcome()) {
+            // e.g. on time, which no turn shows
+            game.game_state = GameState::GameOver { outcome };
+        }
+        Ok(game)
+    }
+
+    /**
+    switches to other rules; only possible before the game has started.
+    */
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        if let GameState::Waiting | GameState::Editing = self.game_state {
+            self.rules = rules;
+            self.clocks = full_clocks(&rules);
+            self.board.apply_rules(&rules);
+        }
+    }
+
+    /**
+    starts the randomness of the game over from `seed`; only possible before
+    the game has started.
+    */
+    pub fn set_seed(&mut self, seed: u64) {
+        if let GameState::Waiting | GameState::Editing = self.game_state {
+            self.seed = seed;
+            self.rng = Rng::new(seed);
+        }
+    }
+
+    /**
+    enters `GameState::Editing`; only possible before the game has started.
+    */
+    pub fn start_editing(&mut self) {
+        if let GameState::Waiting = self.game_state {
+            self.game_state = GameState::Editing;
+            self.editor = Some(Editor::default());
+        }
+    }
+
+    /**
+    leaves the editor if the edited board is playable.
+    otherwise, stays in the editor and tells why.
+    */
+    pub fn finish_editing(&mut self) -> Result<(), BoardError> {
+        if let Err(e) = self.board.verify() {
+            if let Some(editor) = &mut self.editor {
+                editor.message = Some(e.to_string());
+            }
+            return Err(e);
+        }
+        self.game_state = GameState::Waiting;
+        self.editor = None;
+        Ok(())
+    }
+
+    /**
+    lets `bot` play for `player` from now on.
+    */
+    pub fn set_computer(&mut self, player: Player, bot: Box<dyn Bot>) {
+        self.bots.insert(player, bot);
+    }
+
+    /// true if `player`'s moves are chosen by a bot, thinking right now or not
+    pub fn is_computer(&self, player: Player) -> bool {
+        self.bots.contains_key(&player) || self.search.as_ref().is_some_and(|s| s.player == player)
+    }
+
+    pub fn start_game(&mut self, clock: &impl Clock) {
         if let GameState::Waiting = self.game_state {
             self.game_state = GameState::Countdown {
-                started_at: Instant::now(),
+                started_at: clock.now(),
             }
         } else {
             panic!("attempted to start game from state: {:?}", self.game_state);
         }
     }
 
-    pub fn apply_move(&mut self, r#move: &Move, time: f32, move_duration: f32) {
+    pub fn apply_move(&mut self, r#move: &Move, clock: &impl Clock, move_duration: f32) {
         if !self.game_state.allows_moves() || !r#move.object.owned_by(&self.current_player()) {
             return;
         }
-        self.move_to(&r#move.object, r#move.target(), time, move_duration);
-        let mut effects_to_apply = r#move.effects.clone();
-        if let GameState::Playing { move_nr, .. } = self.game_state {
-            // get object effects
-            effects_to_apply.extend(self.tick_objects(move_nr, time));
+        self.play_move(r#move, clock.now(), move_duration);
+    }
+
+    /**
+    restores the position from before the last turn.
+
+    in a replay, this steps back one turn.
+    returns `false` if there was nothing to undo.
+    */
+    pub fn undo(&mut self, clock: &impl Clock) -> bool {
+        let time = clock.now();
+        match self.history.undo() {
+            Some(snapshot) => {
+                let snapshot = snapshot.clone();
+                self.restore(snapshot, time);
+                if let Some(replay) = &mut self.replay {
+                    replay.error = None;
+                    replay.next_turn -= 1;
+                    replay.on_turn_change(time);
+                }
+                true
+            }
+            None => false,
         }
-        // run game logic to setup next move
-        self.apply_effects(&effects_to_apply, time);
+    }
 
-        if let Some(winner) = self.winner() {
-            self.game_state = GameState::GameOver { winner };
-        } else {
-            self.game_state = self.game_state.on_apply_move();
+    /**
+    restores the position from after the next undone turn.
+
+    in a replay, this plays the next turn right away, with animations.
+    returns `false` if there was nothing to redo.
+    */
+    pub fn redo(&mut self, clock: &impl Clock) -> bool {
+        if let Some(replay) = &self.replay {
+            return match replay.peek().cloned() {
+                Some(turn) if matches!(self.game_state, GameState::Replay { .. }) => {
+                    self.play_recorded_turn(&turn, clock.now())
+                }
+                _ => false,
+            };
+        }
+        match self.history.redo() {
+            Some(snapshot) => {
+                let snapshot = snapshot.clone();
+                self.restore(snapshot, clock.now());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /**
+    plays a turn that was decided elsewhere, e.g. by a server.
+    */
+    pub fn play_remote_turn(
+        &mut self,
+        turn: &RecordedTurn,
+        clock: &impl Clock,
+    ) -> Result<(), NotationError> {
+        let time = clock.now();
+        if let GameState::Countdown { .. } = self.game_state {
+            // the server's countdown may have ended a little before ours
+            self.game_state = GameState::Playing {
+                current_player: self.rules.starting_player,
+                move_start: time,
+                move_nr: 0,
+            };
+        }
+        match &turn.notation {
+            Some(notation) => {
+                let r#move = notation.resolve(&self.board, &self.current_player(), &self.rules)?;
+                self.play_move(&r#move, time, CONF.get().move_application_time);
+            }
+            None => self.pass_turn(time),
         }
+        Ok(())
+    }
+
+    fn play_move(&mut self, r#move: &Move, time: f32, move_duration: f32) {
+        let player = self.current_player();
+        if self.out_of_time(player, time) {
+            // the flag fell before the move came in
+            self.flag_fall(player, time);
+            return;
+        }
+        let before = self.snapshot();
+        let think_time = time - self.game_state.move_start().unwrap_or(time);
+        let effects = match self.game_state.move_nr() {
+            Some(move_nr) => {
+                let board = &mut self.board;
+                rules::play_turn(
+                    board,
+                    Some(r#move),
+                    move_nr,
+                    time,
+                    move_duration,
+                    &self.rules,
+                )
+            }
+            None => {
+                // moving outside of a turn triggers nothing
+                rules::move_to(
+                    &mut self.board,
+                    &r#move.object,
+                    r#move.target(),
+                    time,
+                    move_duration,
+                );
+                rules::apply_effects(&mut self.board, &r#move.effects, 0, time, &self.rules)
+            }
+        };
+
+        self.charge_clock(player, think_time);
+        self.game_state = self.game_state.on_apply_move(time);
+        self.count_deaths(&before.board);
+        if let Some(outcome) = self.outcome_after_turn() {
+            self.game_state = GameState::GameOver { outcome };
+        }
+        self.record_turn(
+            TurnRecord {
+                player,
+                r#move: Some(r#move.clone()),
+                think_time,
+                effects,
+                next_state: self.game_state,
+            },
+            before,
+        );
     }
 
     pub fn current_player(&self) -> Player {
         match self.game_state {
-            GameState::Playing { current_player, .. } => current_player,
-            GameState::GameOver { winner } => winner,
+            GameState::Playing { current_player, .. }
+            | GameState::Replay { current_player, .. } => current_player,
+            GameState::GameOver { outcome } => outcome.winner.unwrap_or(Player::God),
             _ => Player::A,
         }
     }
 
+    /**
+    how long until `player`'s flag falls, if they don't move before; `None`
+    when the game isn't on.
+
+    the clock of whoever isn't to move stands still.
+    */
+    pub fn time_left(&self, player: Player, time: f32) -> Option<f32> {
+        let move_start = self.game_state.move_start()?;
+        let bank = self.clocks.get(&player)?;
+        let elapsed = match player == self.current_player() {
+            true => time - move_start,
+            false => 0.0,
+        };
+        let control = self.rules.time_control;
+        Some(control.time_left(bank, elapsed, self.rules.play_move_timeout))
+    }
+
+    /// the hash of the current position, with whoever is to move (see `Board::hash`)
+    pub fn hash(&self) -> u64 {
+        let move_nr = self.game_state.move_nr().unwrap_or_default();
+        self.board.hash(move_nr, self.current_player(), &self.rules)
+    }
+
+    /// everything that resolved on the turn leading up to the current position
+    pub fn last_effects(&self) -> Option<&EffectLog> {
+        self.history.played().last().map(|t| &t.effects)
+    }
+
     pub fn get_piece_at_pos(&self, pos: &ScreenCoord) -> Option<Object> {
         self.get_close_from_vec(pos, &self.board.pieces())
     }
@@ -83,121 +441,245 @@ impl GameController {
     run on start of game loop tick
     - cleans up dead objects
     - resets expired animations to none
-    - swap player's turn if the move time is up
+    - lets the computer move, if it is its turn; a bot thinking in the
+      background has its move played in the first tick after it is done
+    - on flag fall, swap player's turn or end the game
+    - ends a game being played or replayed once it has an outcome
      */
-    pub fn tick(&mut self, time: f32) {
-        let mut kills = vec![];
-        self.board.objects_mut().iter_mut().for_each(|o| {
-            if o.props.dead && !o.statuses.iter().any(|s| !s.is_expired(time)) {
-                kills.push(o.clone());
-            }
-            o.statuses = o
-                .statuses
-                .iter()
-                .filter(|s| !s.is_expired(time))
-                .cloned()
-                .collect();
-        });
-        for obj in kills {
-            self.board.remove_object(&obj);
-        }
+    pub fn tick(&mut self, clock: &impl Clock) {
+        let time = clock.now();
+        rules::remove_expired(&mut self.board, time);
+        self.poll_search(time);
 
         match self.game_state {
-            GameState::Countdown { started_at } => {
-                if started_at.elapsed().as_secs_f32() > CONF.game_start_countdown {
-                    self.game_state = GameState::Playing {
-                        current_player: CONF.starting_player,
-                        move_start: Instant::now(),
-                        move_nr: 0,
-                    }
+            GameState::Countdown { started_at }
+                if time - started_at > CONF.get().game_start_countdown =>
+            {
+                self.game_state = GameState::Playing {
+                    current_player: self.rules.starting_player,
+                    move_start: time,
+                    move_nr: 0,
                 }
             }
             GameState::Playing {
+                current_player,
                 move_start,
                 move_nr,
-                ..
-            } => {
-                if move_start.elapsed().as_secs_f32() > CONF.play_move_timeout {
-                    self.game_state = self.game_state.on_apply_move();
-                    let effects = self.tick_objects(move_nr, time);
-                    self.apply_effects(&effects, time);
+            } if !self.networked
+                && self.bots.contains_key(&current_player)
+                && time - move_start > CONF.get().ai_move_delay =>
+            {
+                self.play_bot_turn(current_player, move_nr, time);
+            }
+            GameState::Playing { current_player, .. } if self.out_of_time(current_player, time) => {
+                self.flag_fall(current_player, time);
+            }
+            GameState::Replay { .. } => {
+                let due = self.replay.as_mut().and_then(|r| r.advance(time));
+                if let Some(turn) = due {
+                    self.play_recorded_turn(&turn, time);
                 }
             }
             _ => {}
         }
 
-        if let Some(winner) = self.winner() {
-            self.game_state = GameState::GameOver { winner };
+        // a board that is being edited or waited on is not a game yet
+        if matches!(
+            self.game_state,
+            GameState::Playing { .. } | GameState::Replay { .. }
+        ) {
+            if let Some(outcome) = rules::outcome(&self.board) {
+                self.game_state = GameState::GameOver { outcome };
+            }
         }
     }
 
-    fn tick_objects(&mut self, move_nr: usize, time: f32) -> Vec<Effect> {
-        self.board
-            .objects_mut()
-            .iter_mut()
-            .flat_map(|o| o.tick(move_nr, time))
-            .collect()
+    fn play_bot_turn(&mut self, player: Player, move_nr: usize, time: f32) {
+        let Some(mut bot) = self.bots.remove(&player) else {
+            return;
+        };
+        // so the bot's choices follow from the seed of the game
+        bot.reseed(self.rng.next_u64());
+        let position = Position::new(&self.board, player, move_nr, &self.rules);
+        if !self.bots_in_background {
+            let choice = bot.choose_move(&position);
+            self.bots.insert(player, bot);
+            self.play_bot_choice(choice, time);
+            return;
+        }
+        let hash = self.hash();
+        let handle = thread::spawn(move || {
+            let choice = bot.choose_move(&position);
+            (bot, choice)
+        });
+        self.search = Some(Search {
+            player,
+            move_nr,
+            hash,
+            handle,
+        });
+    }
+
+    /// plays the move of the bot searching in the background, if it is done
+    fn poll_search(&mut self, time: f32) {
+        let Some(search) = self.search.take_if(|s| s.handle.is_finished()) else {
+            return;
+        };
+        let (bot, choice) = search.handle.join().expect("bot panicked");
+        self.bots.insert(search.player, bot);
+        // the game may have moved on while it was thinking, e.g. by undo or flag fall
+        let still_to_move = match self.game_state {
+            GameState::Playing {
+                current_player,
+                move_nr,
+                ..
+            } => current_player == search.player && move_nr == search.move_nr,
+            _ => false,
+        };
+        if still_to_move && self.hash() == search.hash {
+            self.play_bot_choice(choice, time);
+        }
+    }
+
+    fn play_bot_choice(&mut self, choice: Option<Move>, time: f32) {
+        match choice {
+            Some(r#move) => self.play_move(&r#move, time, CONF.get().move_application_time),
+            None => self.pass_turn(time),
+        }
     }
 
-    fn move_to(&mut self, object: &Object, to: &HexCoord, time: f32, duration: f32) {
-        if let Some(obj) = self.board.get_as_mut(object) {
-            let height = if obj.otype == ObjectType::Jumper {
-                0.5
-            } else {
-                0.0
+    fn pass_turn(&mut self, time: f32) {
+        let before = self.snapshot();
+        let player = self.current_player();
+        let think_time = time - self.game_state.move_start().unwrap_or(time);
+        let move_nr = self.game_state.move_nr().unwrap_or_default();
+        self.charge_clock(player, think_time);
+        self.game_state = self.game_state.on_apply_move(time);
+        let effects = rules::play_turn(&mut self.board, None, move_nr, time, 0.0, &self.rules);
+        self.count_deaths(&before.board);
+        if let Some(outcome) = self.outcome_after_turn() {
+            self.game_state = GameState::GameOver { outcome };
+        }
+        self.record_turn(
+            TurnRecord {
+                player,
+                r#move: None,
+                think_time,
+                effects,
+                next_state: self.game_state,
+            },
+            before,
+        );
+    }
+
+    /**
+    `player` ran out of time: they lose, or their turn is passed and they
+    are on `RuleSet::play_move_timeout` per move from then on.
+    */
+    fn flag_fall(&mut self, player: Player, time: f32) {
+        if self.rules.flag_fall_loses {
+            self.game_state = GameState::GameOver {
+                outcome: GameOutcome::win(player.opponent(), Reason::Time),
             };
-            obj.statuses.push(Status::new_move(
-                ScreenCoord::from_hexcoord(&obj.coord),
-                ScreenCoord::from_hexcoord(to),
-                time,
-                duration,
-                height,
-            ));
-            obj.set_coord(to);
-        }
-    }
-
-    fn winner(&self) -> Option<Player> {
-        let mut player_a_alive = false;
-        let mut player_b_alive = false;
-        for p in self.board.pieces().iter().filter(|p| !p.props.dead) {
-            if p.owned_by(&Player::A) {
-                player_a_alive = true;
-            }
-            if p.owned_by(&Player::B) {
-                player_b_alive = true;
-            }
+            return;
+        }
+        self.pass_turn(time);
+    }
+
+    /// whether `player`'s flag has fallen, on a clock the game keeps itself
+    fn out_of_time(&self, player: Player, time: f32) -> bool {
+        !self.networked
+            && matches!(self.game_state, GameState::Playing { .. })
+            && self.time_left(player, time).is_some_and(|t| t <= 0.0)
+    }
+
+    /// takes the time `player` spent on a turn off their clock; their flag falls if it ran out
+    fn charge_clock(&mut self, player: Player, think_time: f32) {
+        if self.game_state.move_nr().is_none() {
+            return;
         }
-        match (player_a_alive, player_b_alive) {
-            (false, false) => Some(Player::God),
-            (true, false) => Some(Player::A),
-            (false, true) => Some(Player::B),
-            (true, true) => None,
+        if let Some(bank) = self.clocks.get_mut(&player) {
+            let per_move = self.rules.play_move_timeout;
+            *bank = self.rules.time_control.charge(*bank, think_time, per_move);
         }
     }
 
-    fn apply_effects(&mut self, effects: &Vec<Effect>, time: f32) {
-        for effect in effects {
-            match effect {
-                Effect::Kill { victim, .. } => {
-                    if let Some(v) = self.board.get_as_mut(victim) {
-                        let status = effect.applying_status(time);
-                        v.set_killed(status.as_ref());
+    /// returns `false`, and stops the replay, if the turn doesn't fit the board
+    fn play_recorded_turn(&mut self, turn: &RecordedTurn, time: f32) -> bool {
+        match &turn.notation {
+            Some(notation) => {
+                match notation.resolve(&self.board, &self.current_player(), &self.rules) {
+                    Ok(r#move) => self.play_move(&r#move, time, CONF.get().move_application_time),
+                    Err(e) => {
+                        if let Some(replay) = &mut self.replay {
+                            replay.error = Some(e);
+                        }
+                        return false;
                     }
                 }
-                Effect::KillAallOn { coord, .. } => {
-                    let status = effect.applying_status(time);
-                    self.board.kill_all_at(coord, status);
-                }
-                Effect::SetStatus { object, .. } => {
-                    let status = effect.applying_status(time);
-                    if let Some(o) = self.board.get_as_mut(object) {
-                        o.add_status(&status.expect("SetStatus without a status"));
-                    }
-                }
-                Effect::NoOp => {}
             }
+            None => self.pass_turn(time),
+        }
+        if let Some(replay) = &mut self.replay {
+            replay.next_turn += 1;
+            replay.on_turn_change(time);
+        }
+        true
+    }
+
+    /**
+    how the game ends with the turn that was just played, if it does: by
+    elimination, or by a draw under the rules.
+
+    the position the turn led to must not be in the history yet.
+    */
+    fn outcome_after_turn(&self) -> Option<GameOutcome> {
+        if let Some(outcome) = rules::outcome(&self.board) {
+            return Some(outcome);
         }
+        self.game_state.move_nr()?;
+        let hash = self.hash();
+        let seen = self.history.positions().filter(|s| s.hash == hash).count();
+        if self.rules.repetitions > 0 && seen + 1 >= self.rules.repetitions {
+            return Some(GameOutcome::draw(Reason::Repetition));
+        }
+        let limit = self.rules.no_progress_limit;
+        if limit > 0 && self.moves_since_death >= limit {
+            return Some(GameOutcome::draw(Reason::MoveLimit));
+        }
+        None
+    }
+
+    /// starts `moves_since_death` over if a piece died since `before`, or counts the turn
+    fn count_deaths(&mut self, before: &Board) {
+        match living_pieces(&self.board) < living_pieces(before) {
+            true => self.moves_since_death = 0,
+            false => self.moves_since_death += 1,
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            board: self.board.clone(),
+            game_state: self.game_state,
+            clocks: self.clocks.clone(),
+            rng: self.rng,
+            hash: self.hash(),
+            moves_since_death: self.moves_since_death,
+        }
+    }
+
+    fn record_turn(&mut self, record: TurnRecord, before: Snapshot) {
+        let after = self.snapshot();
+        self.history.record(record, before, after);
+    }
+
+    fn restore(&mut self, snapshot: Snapshot, time: f32) {
+        self.board = snapshot.board;
+        self.game_state = snapshot.game_state.restarted_at(time);
+        self.clocks = snapshot.clocks;
+        self.rng = snapshot.rng;
+        self.moves_since_death = snapshot.moves_since_death;
     }
 
     /**
@@ -218,3 +700,26 @@ impl GameController {
         detection.cloned()
     }
 }
+
+/// both players' clocks, before the first move
+fn full_clocks(rules: &RuleSet) -> HashMap<Player, TimeBank> {
+    let bank = rules.time_control.start();
+    HashMap::from([(Player::A, bank), (Player::B, bank)])
+}
+
+/// the pieces on `board` that are still alive, not counting walls
+fn living_pieces(board: &Board) -> usize {
+    let pieces = board.pieces().into_iter();
+    pieces
+        .filter(|p| p.player != Player::God && !p.props.dead)
+        .count()
+}
+
+/// a bot thinking about its move on a thread of its own
+struct Search {
+    player: Player,
+    move_nr: usize,
+    /// the position it is thinking about
+    hash: u64,
+    handle: JoinHandle<(Box<dyn Bot>, Option<Move>)>,
+}
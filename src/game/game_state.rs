@@ -1,29 +1,62 @@
 use crate::engine::Player;
-use std::time::Instant;
+use crate::game::GameOutcome;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/**
+All times are in seconds, as given by the `Clock` driving the game.
+*/
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum GameState {
-    Editing, // anticipating the map-editor
+    Editing,
     Waiting,
     Countdown {
-        started_at: Instant,
+        started_at: f32,
     },
     Playing {
         current_player: Player,
-        move_start: Instant,
+        move_start: f32,
+        move_nr: usize,
+    },
+    /// like `Playing`, but the moves come from a recorded game
+    Replay {
+        current_player: Player,
+        move_start: f32,
         move_nr: usize,
     },
     GameOver {
-        winner: Player,
+        outcome: GameOutcome,
     },
 }
 
 impl GameState {
     pub fn allows_moves(&self) -> bool {
-        !matches!(self, GameState::Waiting | GameState::Countdown { .. })
+        !matches!(
+            self,
+            GameState::Editing
+                | GameState::Waiting
+                | GameState::Countdown { .. }
+                | GameState::Replay { .. }
+        )
+    }
+
+    pub fn move_nr(&self) -> Option<usize> {
+        match self {
+            GameState::Playing { move_nr, .. } | GameState::Replay { move_nr, .. } => {
+                Some(*move_nr)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn move_start(&self) -> Option<f32> {
+        match self {
+            GameState::Playing { move_start, .. } | GameState::Replay { move_start, .. } => {
+                Some(*move_start)
+            }
+            _ => None,
+        }
     }
 
-    pub fn on_apply_move(&self) -> GameState {
+    pub fn on_apply_move(&self, time: f32) -> GameState {
         match self {
             GameState::Playing {
                 current_player,
@@ -31,18 +64,61 @@ impl GameState {
                 ..
             } => GameState::Playing {
                 current_player: current_player.opponent(),
-                move_start: Instant::now(),
+                move_start: time,
+                move_nr: move_nr + 1,
+            },
+            GameState::Replay {
+                current_player,
+                move_nr,
+                ..
+            } => GameState::Replay {
+                current_player: current_player.opponent(),
+                move_start: time,
                 move_nr: move_nr + 1,
             },
             _ => *self,
         }
     }
 
-    pub fn winner(&self) -> Option<Player> {
-        if let GameState::GameOver { winner } = self {
-            Some(*winner)
+    /**
+    the same state, but with its timer starting over at `time`,
+    e.g. when a position is restored from history.
+    */
+    pub fn restarted_at(&self, time: f32) -> GameState {
+        match self {
+            GameState::Countdown { .. } => GameState::Countdown { started_at: time },
+            GameState::Playing {
+                current_player,
+                move_nr,
+                ..
+            } => GameState::Playing {
+                current_player: *current_player,
+                move_start: time,
+                move_nr: *move_nr,
+            },
+            GameState::Replay {
+                current_player,
+                move_nr,
+                ..
+            } => GameState::Replay {
+                current_player: *current_player,
+                move_start: time,
+                move_nr: *move_nr,
+            },
+            _ => *self,
+        }
+    }
+
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        if let GameState::GameOver { outcome } = self {
+            Some(*outcome)
         } else {
             None
         }
     }
+
+    /// `None` while the game is on, and after a draw
+    pub fn winner(&self) -> Option<Player> {
+        self.outcome().and_then(|o| o.winner)
+    }
 }
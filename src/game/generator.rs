@@ -0,0 +1,136 @@
+/*!
+Boards made up from a seed.
+
+The board is a hexagon, and everything on it comes in pairs mirrored through
+the middle: holes, walls, and the pieces of `Player::A` and `Player::B`. The
+tiles fall from the edge inwards, so the arena shrinks as the game goes on.
+*/
+use crate::engine::{Board, Object, ObjectType, Player, RuleSet};
+use crate::game::moves::legal_moves;
+use crate::geometry::{CubeCoord, HexCoord};
+use crate::rng::Rng;
+
+/// boards that don't work out (e.g. a side without moves) are made again, up to this many times
+const ATTEMPTS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Generator {
+    /// steps from the middle of the board to its edge
+    pub radius: usize,
+    /// holes in the board, not counting their mirror images
+    pub holes: usize,
+    /// walls on each side of the board
+    pub walls: usize,
+    /// pieces of each kind for each player
+    pub dashers: usize,
+    pub jumpers: usize,
+    pub pushers: usize,
+    pub bombers: usize,
+    /// the move on which the edge falls
+    pub first_fall: usize,
+    /// the move on which the middle falls
+    pub last_fall: usize,
+    /// how many moves a tile may fall earlier or later than the schedule says
+    pub jitter: usize,
+}
+
+impl Default for Generator {
+    fn default() -> Generator {
+        Generator {
+            radius: 4,
+            holes: 2,
+            walls: 2,
+            dashers: 3,
+            jumpers: 3,
+            pushers: 0,
+            bombers: 0,
+            first_fall: 20,
+            last_fall: 48,
+            jitter: 2,
+        }
+    }
+}
+
+impl Generator {
+    /**
+    a board made up from `seed`; the same seed always makes the same board.
+    both players have a legal move on it by `rules`, which also decide when
+    the tiles start to show that they are about to fall.
+
+    `None` if no such board came out, e.g. as there are more pieces than fit.
+    */
+    pub fn generate(&self, seed: u64, rules: &RuleSet) -> Option<Board> {
+        let mut rng = Rng::new(seed);
+        (0..ATTEMPTS).find_map(|_| self.attempt(&mut rng, rules))
+    }
+
+    fn attempt(&self, rng: &mut Rng, rules: &RuleSet) -> Option<Board> {
+        let size = 2 * self.radius + 1;
+        let middle = CubeCoord::new(self.radius as i32, self.radius as i32);
+        let mirror = |c: &HexCoord| c.cube().reflect_point(&middle).to_hexcoord(size).unwrap();
+        // player A's half of the board: everything above the middle row, and left of the middle
+        let mut half: Vec<HexCoord> = middle
+            .range(self.radius)
+            .into_iter()
+            .filter(|c| {
+                let v = *c - middle;
+                v.r < 0 || (v.r == 0 && v.q < 0)
+            })
+            .filter_map(|c| c.to_hexcoord(size))
+            .collect();
+        rng.shuffle(&mut half);
+
+        // holes are left without tiles
+        half.drain(..self.holes.min(half.len()));
+        let middle_coord = middle.to_hexcoord(size).unwrap();
+        let mut objects = vec![Object::new_tile(0, middle_coord, self.last_fall, rules)];
+        let per_step =
+            self.last_fall.saturating_sub(self.first_fall) as f32 / self.radius.max(1) as f32;
+        for coord in &half {
+            let steps = coord.distance(&middle_coord);
+            let scheduled = self
+                .last_fall
+                .saturating_sub(Board::fall_delay(steps, per_step));
+            let jitter = rng.below(2 * self.jitter + 1);
+            let lifespan = (scheduled + jitter).saturating_sub(self.jitter).max(1);
+            for c in [*coord, mirror(coord)] {
+                let oid = objects.len();
+                objects.push(Object::new_tile(oid, c, lifespan, rules));
+            }
+        }
+
+        let walls: Vec<HexCoord> = half.drain(..self.walls.min(half.len())).collect();
+        for coord in walls {
+            for c in [coord, mirror(&coord)] {
+                objects.push(Object::new_wall(objects.len(), c));
+            }
+        }
+        // pieces start away from the middle row, where the other side can't be next to them
+        let mut spots = half.into_iter().filter(|c| c.cube().r < middle.r);
+        let kinds = [
+            (ObjectType::Dasher, self.dashers),
+            (ObjectType::Jumper, self.jumpers),
+            (ObjectType::Pusher, self.pushers),
+            (ObjectType::Bomber, self.bombers),
+        ];
+        for (otype, count) in kinds {
+            for _ in 0..count {
+                let coord = spots.next()?;
+                let oid = objects.len();
+                objects.push(Object::new(oid, otype, coord, Player::A));
+                let oid = objects.len();
+                objects.push(Object::new(oid, otype, mirror(&coord), Player::B));
+            }
+        }
+
+        let board = Board::new(size, objects).ok()?;
+        let has_moves = |player: Player| {
+            board
+                .pieces()
+                .into_iter()
+                .filter(|p| p.owned_by(&player))
+                .any(|p| !legal_moves(p, &board, rules).is_empty())
+        };
+        (has_moves(Player::A) && has_moves(Player::B)).then_some(board)
+    }
+}
@@ -0,0 +1,128 @@
+use crate::engine::{Board, Player, TimeBank};
+use crate::game::{effect_queue::EffectLog, moves::Move, GameState};
+use crate::rng::Rng;
+use std::collections::HashMap;
+
+/**
+What happened during one turn.
+
+A turn either has a `Move`, or was passed because the move timed out.
+*/
+#[derive(Debug, Clone)]
+pub struct TurnRecord {
+    pub player: Player,
+    pub r#move: Option<Move>,
+    /// seconds from the start of the turn until it was played (or passed)
+    pub think_time: f32,
+    /// every effect that resolved on this turn: the move's own (e.g. captures),
+    /// the ones objects triggered (e.g. `DelayedEffect`s), and what they caused
+    pub effects: EffectLog,
+    /// the state the game was in after the turn was over
+    pub next_state: GameState,
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub board: Board,
+    pub game_state: GameState,
+    /// what was left on the players' clocks
+    pub clocks: HashMap<Player, TimeBank>,
+    /// the randomness of the game, as it was
+    pub rng: Rng,
+    /// see `GameController::moves_since_death`
+    pub moves_since_death: usize,
+    /// the hash of the position (see `Board::hash`)
+    pub hash: u64,
+}
+
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    record: TurnRecord,
+    before: Snapshot,
+    after: Snapshot,
+}
+
+/**
+Every turn played so far, with enough information to step back and forth.
+
+Entries after the cursor are the ones that have been undone; recording a
+new turn discards them.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    cursor: usize,
+}
+
+impl History {
+    pub fn record(&mut self, record: TurnRecord, before: Snapshot, after: Snapshot) {
+        self.entries.truncate(self.cursor);
+        self.entries.push(HistoryEntry {
+            record,
+            before,
+            after,
+        });
+        self.cursor = self.entries.len();
+    }
+
+    /// steps back one turn, returning the position from before it was played
+    pub fn undo(&mut self) -> Option<&Snapshot> {
+        if self.cursor == 0 {
+            return None;
+        }
+        self.cursor -= 1;
+        Some(&self.entries[self.cursor].before)
+    }
+
+    /// steps forward one turn, returning the position from after it was played
+    pub fn redo(&mut self) -> Option<&Snapshot> {
+        if self.cursor == self.entries.len() {
+            return None;
+        }
+        self.cursor += 1;
+        Some(&self.entries[self.cursor - 1].after)
+    }
+
+    /// the position the first recorded turn was played from
+    pub fn first_position(&self) -> Option<&Snapshot> {
+        self.entries.first().map(|e| &e.before)
+    }
+
+    /// every position up to the current one, from the first
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = &Snapshot> {
+        let first = self.entries[..self.cursor].first().map(|e| &e.before);
+        first
+            .into_iter()
+            .chain(self.entries[..self.cursor].iter().map(|e| &e.after))
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.entries.len()
+    }
+
+    /// the turns leading up to the current position
+    pub fn played(&self) -> impl Iterator<Item = &TurnRecord> {
+        self.entries[..self.cursor].iter().map(|e| &e.record)
+    }
+
+    /// all recorded turns, including undone ones
+    pub fn records(&self) -> impl Iterator<Item = &TurnRecord> {
+        self.entries.iter().map(|e| &e.record)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+}
@@ -1,6 +1,17 @@
+mod clock;
+pub mod editor;
+pub mod effect_queue;
 mod game_controller;
 mod game_state;
+pub mod generator;
+pub mod history;
 pub mod moves;
+pub mod notation;
+mod outcome;
+pub mod replay;
+pub mod rules;
 
+pub use clock::{Clock, ManualClock, WallClock};
 pub use game_controller::GameController;
 pub use game_state::GameState;
+pub use outcome::{GameOutcome, Reason};
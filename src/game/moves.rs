@@ -1,6 +1,6 @@
-use crate::config::CONF;
-use crate::engine::statuses::Effect;
-use crate::engine::{Board, Object, ObjectType, Player};
+use crate::engine::statuses::{Effect, StatusType};
+use crate::engine::{Board, Object, ObjectType, Player, RuleSet, TileType};
+use crate::game::rules;
 use crate::geometry::{HexCoord, ScreenCoord};
 
 #[derive(Debug, Clone)]
@@ -27,12 +27,40 @@ impl Move {
     }
 }
 
-pub fn legal_moves(object: &Object, board: &Board) -> Vec<Move> {
-    match object.otype {
-        ObjectType::Dasher => dasher_moves(object, board),
+pub fn legal_moves(object: &Object, board: &Board, rules: &RuleSet) -> Vec<Move> {
+    let moves = match object.otype {
+        ObjectType::Dasher => dasher_moves(object, board, rules),
         ObjectType::Jumper => jumper_moves(object, board),
+        ObjectType::Pusher => pusher_moves(object, board),
+        ObjectType::Bomber => bomber_moves(object, board, rules),
         _ => vec![],
+    };
+    moves.into_iter().map(|m| teleported(m, board)).collect()
+}
+
+/**
+a piece that ends its move on a teleporter goes on to the partner, if nothing
+is standing there.
+*/
+fn teleported(mut r#move: Move, board: &Board) -> Move {
+    let target = *r#move.target();
+    if target == r#move.object.coord {
+        return r#move;
     }
+    let partner = board
+        .tile_at(&target)
+        .filter(|t| !t.props.dead)
+        .and_then(|t| board.teleporter_partner(t));
+    if let Some(p) = partner {
+        // the mover itself may be on the partner, but it is leaving
+        if p.coord == r#move.object.coord || board.is_empty(&p.coord) {
+            r#move.effects.push(Effect::Teleport {
+                object: r#move.object.clone(),
+                to: p.coord,
+            });
+        }
+    }
+    r#move
 }
 
 fn jumper_moves(object: &Object, board: &Board) -> Vec<Move> {
@@ -54,11 +82,11 @@ fn jumper_moves(object: &Object, board: &Board) -> Vec<Move> {
         Move::new(obj.clone(), vec![obj.coord, *inter, target], effects)
     }
     fn get_hook(obj: &Object, dir: usize, clockw: bool, board: &Board) -> Option<Move> {
-        let intermediate = obj.coord.get_neighbor(dir, 2);
+        let intermediate = board.neighbor(&obj.coord, dir, 2);
         match intermediate {
             Some(inter) => {
                 let hook_dir = (dir + if clockw { 1 } else { 5 }) % 6;
-                if let Some(target) = inter.get_neighbor(hook_dir, 1) {
+                if let Some(target) = board.neighbor(&inter, hook_dir, 1) {
                     if tile_available_for_step(&target, board, Some(obj.player.opponent())) {
                         return Some(create_move(obj, target, &inter, board));
                     }
@@ -83,7 +111,7 @@ fn jumper_moves(object: &Object, board: &Board) -> Vec<Move> {
         .collect()
 }
 
-fn dasher_moves(object: &Object, board: &Board) -> Vec<Move> {
+fn dasher_moves(object: &Object, board: &Board, rules: &RuleSet) -> Vec<Move> {
     fn create_move(path: Vec<HexCoord>, obj: &Object, effects: Vec<Effect>) -> Move {
         Move::new(obj.clone(), path, effects)
     }
@@ -96,21 +124,33 @@ fn dasher_moves(object: &Object, board: &Board) -> Vec<Move> {
         0.65 * victim_dist / full_dist
     }
 
-    fn create_path(obj: &Object, dir: &usize, board: &Board) -> (Vec<HexCoord>, Vec<Effect>) {
+    fn create_path(
+        obj: &Object,
+        dir: &usize,
+        board: &Board,
+        rules: &RuleSet,
+    ) -> (Vec<HexCoord>, Vec<Effect>) {
         let mut path = vec![];
         let mut curr = Some(obj.coord);
         let mut victims_and_coords = vec![];
         while let Some(c) = curr {
             path.push(c);
-            let next_tile = c.get_neighbor(*dir, 1);
+            let next_tile = board.neighbor(&c, *dir, 1);
             match next_tile {
                 Some(next) => {
                     if tile_available_for_step(&next, board, Some(obj.player.opponent()))
-                        || CONF.dasher_can_fly && board.tile_at(&next).is_none()
+                        || rules.dasher_can_fly && board.tile_at(&next).is_none_or(|t| t.props.dead)
                     {
                         if let Some(victim) = board.contents(&next) {
                             victims_and_coords.push((victim, next));
                         }
+                    } else if let Some(after) = slide(obj, &c, &next, *dir, board) {
+                        path.push(next);
+                        if let Some(victim) = board.contents(&after) {
+                            victims_and_coords.push((victim, after));
+                        }
+                        curr = Some(after);
+                        continue;
                     } else {
                         break;
                     }
@@ -130,16 +170,91 @@ fn dasher_moves(object: &Object, board: &Board) -> Vec<Move> {
 
         (path, effects)
     }
+    /// coming off ice onto a gap, the Dasher slides over it if there is a tile behind
+    fn slide(
+        obj: &Object,
+        from: &HexCoord,
+        gap: &HexCoord,
+        dir: usize,
+        board: &Board,
+    ) -> Option<HexCoord> {
+        let on_ice = board
+            .tile_at(from)
+            .is_some_and(|t| t.tile_type() == TileType::Ice && !t.props.dead);
+        let is_gap = board.tile_at(gap).is_none_or(|t| t.props.dead);
+        let after = board.neighbor(gap, dir, 1)?;
+        (on_ice && is_gap && tile_available_for_step(&after, board, Some(obj.player.opponent())))
+            .then_some(after)
+    }
     object
         .coord
         .get_all_directions()
         .iter()
-        .map(|dir| create_path(object, dir, board))
+        .map(|dir| create_path(object, dir, board, rules))
         .filter(|(p, _)| p.len() > 1)
         .map(|(p, es)| create_move(p, object, es))
         .collect()
 }
 
+/**
+one step in any direction. a piece standing there is shoved along, together
+with any pieces lined up behind it, unless the line ends in a wall.
+*/
+fn pusher_moves(object: &Object, board: &Board) -> Vec<Move> {
+    let create_move = |dir: usize| -> Option<Move> {
+        let target = board.neighbor(&object.coord, dir, 1)?;
+        if !tile_available_for_step(&target, board, None) {
+            return None;
+        }
+        let effects = match board.contents(&target) {
+            Some(piece) => {
+                rules::push_chain(board, piece, dir)?;
+                vec![Effect::Push {
+                    object: piece.clone(),
+                    direction: dir,
+                    animation_delay_frac: Some(0.5),
+                }]
+            }
+            None => vec![],
+        };
+        Some(Move::new(
+            object.clone(),
+            vec![object.coord, target],
+            effects,
+        ))
+    };
+    object
+        .coord
+        .get_all_directions()
+        .into_iter()
+        .filter_map(create_move)
+        .collect()
+}
+
+/**
+one step onto an empty tile, or, if the fuse is not lit yet, lighting it
+without moving.
+*/
+fn bomber_moves(object: &Object, board: &Board, rules: &RuleSet) -> Vec<Move> {
+    let steps = board
+        .neighbours(&object.coord, 1)
+        .into_iter()
+        .filter(|c| tile_available_for_step(c, board, None) && board.contents(c).is_none())
+        .map(|c| Move::new(object.clone(), vec![object.coord, c], vec![]));
+    let lit = object
+        .statuses
+        .iter()
+        .any(|s| matches!(s.stype, StatusType::DelayedEffect { .. }));
+    let arm = (!lit).then(|| {
+        let effects = vec![Effect::Arm {
+            object: object.clone(),
+            fuse: rules.bomber_fuse,
+        }];
+        Move::new(object.clone(), vec![object.coord, object.coord], effects)
+    });
+    steps.chain(arm).collect()
+}
+
 /**
 tells if a tile is:
 - existing
@@ -160,10 +275,11 @@ fn tile_available_for_step(
         }
         None => return false,
     }
-    // true if the tile contains no piece, or a piece owned by the tolerated player
+    // true if the tile contains no live piece, or a piece owned by the tolerated player
+    // (pieces killed earlier may linger for their animation, but don't block)
     match tolerated_player {
         Some(tolerated) => board
-            .piece_at(tile_coord)
+            .contents(tile_coord)
             .map(|p| p.owned_by(&tolerated))
             .unwrap_or_else(|| true),
         None => true,
@@ -0,0 +1,600 @@
+//! Text notation for moves and whole games.
+//!
+//! # Coordinates
+//!
+//! A `HexCoord` is written as a file letter followed by a rank number, like
+//! chess: `x` is the letter (`a` is 0) and `y` is the number (starting at 1).
+//! So `HexCoord { x: 2, y: 4 }` is `c5`. Past `z` the files go on as
+//! spreadsheet columns do: `aa`, `ab`, ..., `az`, `ba`, ...
+//!
+//! # Moves
+//!
+//! ```text
+//! <piece><from>-<waypoint>-...-<to>x<capture>x<capture>...
+//! ```
+//!
+//! - `piece` is `D` (Dasher), `J` (Jumper), `P` (Pusher) or `B` (Bomber).
+//! - the path lists every coord where the piece changes direction, so a Dasher
+//!   is written `Db2-b6` and a Jumper `Jd4-d6-c6`. When parsing, any subset of
+//!   the waypoints may be left out as long as the move is unambiguous; the
+//!   ones that are written must come in the order the piece passes them.
+//!   A Bomber lighting its own fuse stays put, and is written `Bc3-c3`.
+//! - every captured piece is listed after an `x`, in the order they are hit.
+//!   Only an `x` right after a rank number starts a capture, so files with an
+//!   `x` in them can still be read.
+//!   A move is only accepted if it captures exactly the pieces written down,
+//!   which catches records that have gone out of sync with the rules.
+//! - a turn that was passed because the move timer ran out is written `--`.
+//! - a turn may be followed by a comment holding the number of seconds the
+//!   player spent on it, like `Db2-b6 {1.52}`. Replays use it for pacing.
+//!
+//! # Game records
+//!
+//! A record is a block of `[Key "Value"]` headers followed by the turns,
+//! PGN-style. The `Setup` header holds the starting board as a map (see
+//! `engine::map`) with its lines joined by `|`; without it the game is assumed
+//! to be played on `Board::test_square`. The `Rules` header holds the
+//! `RuleSet` the game was played by, as written by its `Display`, and the
+//! `Seed` header the seed of the `GameController`'s randomness.
+//! Turns are numbered in pairs, other `{...}` comments are ignored, and
+//! the record ends with the result: `1-0` (A won), `0-1` (B won), `1/2-1/2`
+//! (a draw) or `*` (not finished). A finished game also says how it ended in
+//! a `Termination` header, as written by `GameOutcome`'s `Display`.
+//! A saved game (see `GameController::save`) is a record that also has a
+//! `MoveTime` header, the seconds spent on the move that was going on, and a
+//! `RngState` header, where the randomness of the game was at.
+//!
+//! ```text
+//! [Setup "size 7|tiles|41 44 ...|pieces|J . . ..."]
+//! [BoardSize "7"]
+//! [PlayerA "alice"]
+//! [PlayerB "bob"]
+//! [Rules "standard starting_player=A play_move_timeout=5 ..."]
+//! [Seed "8125636405126393361"]
+//! [Result "*"]
+//!
+//! 1. Db2-b6xb5 {2.10} Jg4-e4-d4 {0.87} 2. -- {5.01} Jf2-f4-e4xe4 {3.40} *
+//! ```
+
+use crate::config::CONF;
+use crate::engine::{statuses::Effect, Board, MapError, ObjectType, Player, RuleSet};
+use crate::game::{
+    moves::{legal_moves, Move},
+    GameController, ManualClock,
+};
+use crate::geometry::HexCoord;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotationError {
+    Syntax(String),
+    UnknownPiece(char),
+    CoordOutOfBounds(String),
+    NoPieceAt(HexCoord),
+    NotYourPiece(HexCoord),
+    IllegalMove(String),
+    Ambiguous(String),
+    MissingHeader(&'static str),
+    Map(MapError),
+    BadRules(String),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::Syntax(s) => write!(f, "syntax error: {}", s),
+            NotationError::UnknownPiece(c) => write!(f, "unknown piece: '{}'", c),
+            NotationError::CoordOutOfBounds(s) => write!(f, "coord out of bounds: {}", s),
+            NotationError::NoPieceAt(c) => write!(f, "no piece at {}", coord_to_string(c)),
+            NotationError::NotYourPiece(c) => {
+                write!(
+                    f,
+                    "piece at {} is not the current player's",
+                    coord_to_string(c)
+                )
+            }
+            NotationError::IllegalMove(s) => write!(f, "illegal move: {}", s),
+            NotationError::Ambiguous(s) => write!(f, "ambiguous move: {}", s),
+            NotationError::MissingHeader(h) => write!(f, "missing header: {}", h),
+            NotationError::Map(e) => write!(f, "bad setup: {}", e),
+            NotationError::BadRules(s) => write!(f, "bad rules: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+/**
+A move as written down; not yet checked against a board.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveNotation {
+    pub piece: ObjectType,
+    /// from, any waypoints, and to
+    pub path: Vec<HexCoord>,
+    pub captures: Vec<HexCoord>,
+}
+
+impl MoveNotation {
+    pub fn from_move(r#move: &Move) -> MoveNotation {
+        let path = &r#move.path;
+        let turning_points = path
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| {
+                if *i == 0 || *i == path.len() - 1 {
+                    return true;
+                }
+                step(&path[i - 1], c) != step(c, &path[i + 1])
+            })
+            .map(|(_, c)| *c)
+            .collect();
+        MoveNotation {
+            piece: r#move.object.otype,
+            path: turning_points,
+            captures: captures_of(r#move),
+        }
+    }
+
+    pub fn parse(text: &str, board_size: usize) -> Result<MoveNotation, NotationError> {
+        let mut chars = text.chars();
+        let piece = match chars.next() {
+            Some('D') => ObjectType::Dasher,
+            Some('J') => ObjectType::Jumper,
+            Some('P') => ObjectType::Pusher,
+            Some('B') => ObjectType::Bomber,
+            Some(c) => return Err(NotationError::UnknownPiece(c)),
+            None => return Err(NotationError::Syntax("empty move".to_string())),
+        };
+        let mut parts = split_captures(chars.as_str()).into_iter();
+        let path = parts
+            .next()
+            .unwrap_or_default()
+            .split('-')
+            .map(|c| parse_coord(c, board_size))
+            .collect::<Result<Vec<HexCoord>, NotationError>>()?;
+        if path.len() < 2 {
+            return Err(NotationError::Syntax(format!(
+                "move needs a source and a target: {}",
+                text
+            )));
+        }
+        let captures = parts
+            .map(|c| parse_coord(c, board_size))
+            .collect::<Result<Vec<HexCoord>, NotationError>>()?;
+        Ok(MoveNotation {
+            piece,
+            path,
+            captures,
+        })
+    }
+
+    /**
+    finds the legal move on `board` that this notation describes.
+    */
+    pub fn resolve(
+        &self,
+        board: &Board,
+        player: &Player,
+        rules: &RuleSet,
+    ) -> Result<Move, NotationError> {
+        let (Some(from), Some(to)) = (self.path.first(), self.path.last()) else {
+            return Err(NotationError::Syntax("move without a path".to_string()));
+        };
+        let object = board
+            .contents(from)
+            .ok_or(NotationError::NoPieceAt(*from))?;
+        if !object.owned_by(player) {
+            return Err(NotationError::NotYourPiece(*from));
+        }
+        if object.otype != self.piece {
+            return Err(NotationError::IllegalMove(format!(
+                "{} is a {:?}",
+                coord_to_string(from),
+                object.otype
+            )));
+        }
+        let candidates: Vec<Move> = legal_moves(object, board, rules)
+            .into_iter()
+            .filter(|m| m.target() == to)
+            .filter(|m| passes_in_order(&m.path, &self.path))
+            .filter(|m| captures_of(m) == self.captures)
+            .collect();
+        match candidates.len() {
+            0 => Err(NotationError::IllegalMove(self.to_string())),
+            1 => Ok(candidates.into_iter().next().unwrap()),
+            _ => Err(NotationError::Ambiguous(self.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for MoveNotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let piece = match self.piece {
+            ObjectType::Dasher => 'D',
+            ObjectType::Jumper => 'J',
+            ObjectType::Pusher => 'P',
+            ObjectType::Bomber => 'B',
+            _ => '?',
+        };
+        let path: Vec<String> = self.path.iter().map(coord_to_string).collect();
+        write!(f, "{}{}", piece, path.join("-"))?;
+        for c in &self.captures {
+            write!(f, "x{}", coord_to_string(c))?;
+        }
+        Ok(())
+    }
+}
+
+/**
+One turn of a `GameRecord`.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedTurn {
+    /// `None` if the turn was passed on timeout
+    pub notation: Option<MoveNotation>,
+    /// seconds the player spent on the turn, if known
+    pub think_time: Option<f32>,
+}
+
+/**
+A whole game: headers describing the setup, and every turn played.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    pub headers: Vec<(String, String)>,
+    pub turns: Vec<RecordedTurn>,
+}
+
+impl GameRecord {
+    pub fn from_controller(game: &GameController) -> GameRecord {
+        let outcome = game.game_state.outcome();
+        let result = outcome.map_or("*", |o| o.result());
+        let setup = match game.history.first_position() {
+            Some(snapshot) => &snapshot.board,
+            None => &game.board,
+        };
+        let headers = [
+            ("Setup", setup_to_string(setup)),
+            ("BoardSize", game.board.size.to_string()),
+            ("PlayerA", "?".to_string()),
+            ("PlayerB", "?".to_string()),
+            ("Rules", game.rules.to_string()),
+            ("Seed", game.seed.to_string()),
+            ("Result", result.to_string()),
+        ];
+        let mut record = GameRecord {
+            headers: headers
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+            turns: game
+                .history
+                .played()
+                .map(|t| RecordedTurn {
+                    notation: t.r#move.as_ref().map(MoveNotation::from_move),
+                    // rounded to what survives a round-trip through text
+                    think_time: Some((100.0 * t.think_time).round() / 100.0),
+                })
+                .collect(),
+        };
+        if let Some(outcome) = outcome {
+            record.set_header("Termination", &outcome.to_string());
+        }
+        record
+    }
+
+    pub fn parse(text: &str) -> Result<GameRecord, NotationError> {
+        let mut headers = vec![];
+        let mut body = String::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| NotationError::Syntax(format!("unclosed header: {}", line)))?;
+                let (key, value) = header
+                    .split_once(' ')
+                    .ok_or_else(|| NotationError::Syntax(format!("bad header: {}", line)))?;
+                let value = value.trim().trim_matches('"');
+                headers.push((key.to_string(), value.to_string()));
+            } else {
+                body.push_str(line);
+                body.push(' ');
+            }
+        }
+        let mut record = GameRecord {
+            headers,
+            turns: vec![],
+        };
+        let board_size: usize = record
+            .header("BoardSize")
+            .ok_or(NotationError::MissingHeader("BoardSize"))?
+            .parse()
+            .map_err(|_| NotationError::Syntax("BoardSize is not a number".to_string()))?;
+
+        for token in tokenize(&body) {
+            if is_turn_number(&token) || is_result(&token) {
+                continue;
+            }
+            if let Some(comment) = token.strip_prefix('{') {
+                let think_time = comment.trim_end_matches('}').trim().parse::<f32>();
+                if let (Ok(t), Some(turn)) = (think_time, record.turns.last_mut()) {
+                    turn.think_time = Some(t);
+                }
+                continue;
+            }
+            let notation = if token == "--" {
+                None
+            } else {
+                Some(MoveNotation::parse(&token, board_size)?)
+            };
+            record.turns.push(RecordedTurn {
+                notation,
+                think_time: None,
+            });
+        }
+        Ok(record)
+    }
+
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn set_header(&mut self, key: &str, value: &str) {
+        match self.headers.iter_mut().find(|(k, _)| k == key) {
+            Some(header) => header.1 = value.to_string(),
+            None => self.headers.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    /**
+    plays the record from the start on a fresh, headless `GameController`.
+
+    fails on the first turn that isn't legal in the position it is played in.
+    */
+    pub fn replay(&self) -> Result<GameController, NotationError> {
+        self.replay_from(0.0)
+    }
+
+    /// like `replay`, with the clock of the game starting at `start`
+    pub fn replay_from(&self, start: f32) -> Result<GameController, NotationError> {
+        let mut clock = ManualClock::new(start);
+        let mut game = GameController::with_board(self.board()?);
+        game.set_rules(self.rules()?);
+        game.set_seed(self.seed()?.unwrap_or_default());
+        game.start_game(&clock);
+        clock.advance(CONF.get().game_start_countdown + 0.01);
+        game.tick(&clock);
+        // the turns were played in time, or they wouldn't be in the record
+        game.networked = true;
+        for turn in &self.turns {
+            // so the clocks run down as they did in the game
+            clock.advance(turn.think_time.unwrap_or_default());
+            game.play_remote_turn(turn, &clock)?;
+        }
+        game.networked = false;
+        Ok(game)
+    }
+
+    /// how long the clock of the game ran in `replay`, up to the last turn
+    pub fn duration(&self) -> f32 {
+        let thinking: f32 = self.turns.iter().filter_map(|t| t.think_time).sum();
+        CONF.get().game_start_countdown + 0.01 + thinking
+    }
+
+    /// the board the game started on
+    pub fn board(&self) -> Result<Board, NotationError> {
+        match self.header("Setup") {
+            Some(setup) => setup_to_board(setup),
+            None => Ok(Board::test_square()),
+        }
+    }
+
+    /**
+    the rules the game was played by; standard ones if the record doesn't say.
+
+    older records give the starting player and whether Dashers fly in
+    headers of their own.
+    */
+    pub fn rules(&self) -> Result<RuleSet, NotationError> {
+        let mut rules = match self.header("Rules") {
+            Some(rules) => RuleSet::parse(rules).map_err(NotationError::BadRules)?,
+            None => RuleSet::standard(),
+        };
+        if let Some(player) = self.header("StartingPlayer") {
+            rules.starting_player = match player {
+                "A" => Player::A,
+                "B" => Player::B,
+                _ => {
+                    return Err(NotationError::BadRules(format!(
+                        "StartingPlayer {}",
+                        player
+                    )))
+                }
+            };
+        }
+        if let Some(fly) = self.header("DasherCanFly") {
+            rules.dasher_can_fly = fly
+                .parse()
+                .map_err(|_| NotationError::BadRules(format!("DasherCanFly {}", fly)))?;
+        }
+        Ok(rules)
+    }
+
+    /// the seed the game's randomness started from; older records don't have one
+    pub fn seed(&self) -> Result<Option<u64>, NotationError> {
+        self.header("Seed")
+            .map(|seed| {
+                seed.parse()
+                    .map_err(|_| NotationError::Syntax(format!("Seed is not a number: {}", seed)))
+            })
+            .transpose()
+    }
+
+    fn result(&self) -> &str {
+        self.header("Result").unwrap_or("*")
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.headers {
+            writeln!(f, "[{} \"{}\"]", key, value)?;
+        }
+        writeln!(f)?;
+        for (i, turn) in self.turns.iter().enumerate() {
+            if i % 2 == 0 {
+                write!(f, "{}. ", i / 2 + 1)?;
+            }
+            match &turn.notation {
+                Some(notation) => write!(f, "{} ", notation)?,
+                None => write!(f, "-- ")?,
+            }
+            if let Some(t) = turn.think_time {
+                write!(f, "{{{:.2}}} ", t)?;
+            }
+        }
+        writeln!(f, "{}", self.result())
+    }
+}
+
+pub fn coord_to_string(coord: &HexCoord) -> String {
+    let mut file = vec![];
+    let mut n = coord.x + 1;
+    while n > 0 {
+        n -= 1;
+        file.push(b'a' + (n % 26) as u8);
+        n /= 26;
+    }
+    file.reverse();
+    format!("{}{}", String::from_utf8(file).unwrap(), coord.y + 1)
+}
+
+pub fn parse_coord(text: &str, board_size: usize) -> Result<HexCoord, NotationError> {
+    let bad = || NotationError::Syntax(format!("bad coord: '{}'", text));
+    let digits = text
+        .find(|c: char| !c.is_ascii_lowercase())
+        .ok_or_else(bad)?;
+    let (file, rank) = text.split_at(digits);
+    if file.is_empty() {
+        return Err(bad());
+    }
+    let x = file
+        .bytes()
+        .try_fold(0usize, |x, c| {
+            x.checked_mul(26)?.checked_add((c - b'a') as usize + 1)
+        })
+        .ok_or_else(|| NotationError::CoordOutOfBounds(text.to_string()))?
+        - 1;
+    let y = rank
+        .parse::<usize>()
+        .ok()
+        .filter(|y| *y > 0)
+        .ok_or_else(bad)?
+        - 1;
+    if x >= board_size || y >= board_size {
+        return Err(NotationError::CoordOutOfBounds(text.to_string()));
+    }
+    Ok(HexCoord::new(x, y, board_size))
+}
+
+/// a board as a map on a single line, with `|` for line breaks
+pub fn setup_to_string(board: &Board) -> String {
+    board
+        .to_map()
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect::<Vec<&str>>()
+        .join("|")
+}
+
+pub fn setup_to_board(setup: &str) -> Result<Board, NotationError> {
+    Board::from_map(&setup.replace('|', "\n")).map_err(NotationError::Map)
+}
+
+fn captures_of(r#move: &Move) -> Vec<HexCoord> {
+    r#move
+        .effects
+        .iter()
+        .filter_map(|e| match e {
+            Effect::Kill { victim, .. } => Some(victim.coord),
+            _ => None,
+        })
+        .collect()
+}
+
+/// the part of a move before the captures, and each capture
+fn split_captures(text: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut after_rank = false;
+    for (i, c) in text.char_indices() {
+        if c == 'x' && after_rank {
+            parts.push(&text[start..i]);
+            start = i + 1;
+        }
+        after_rank = c.is_ascii_digit();
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// true if `path` goes through all of `waypoints`, in that order
+fn passes_in_order(path: &[HexCoord], waypoints: &[HexCoord]) -> bool {
+    let mut path = path.iter();
+    waypoints.iter().all(|w| path.any(|c| c == w))
+}
+
+fn step(from: &HexCoord, to: &HexCoord) -> (i32, i32) {
+    (to.x as i32 - from.x as i32, to.y as i32 - from.y as i32)
+}
+
+/**
+splits on whitespace, except that a `{...}` comment is kept as one token.
+*/
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut in_comment = false;
+    for c in text.chars() {
+        match c {
+            '{' if !in_comment => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                in_comment = true;
+                current.push(c);
+            }
+            '}' if in_comment => {
+                current.push(c);
+                tokens.push(std::mem::take(&mut current));
+                in_comment = false;
+            }
+            c if c.is_whitespace() && !in_comment => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn is_turn_number(token: &str) -> bool {
+    token
+        .strip_suffix('.')
+        .is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_result(token: &str) -> bool {
+    matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*")
+}
@@ -0,0 +1,134 @@
+use crate::engine::Player;
+use std::fmt;
+
+/**
+Why a game ended.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// the loser has no pieces left
+    Elimination,
+    /// the loser ran out of time
+    Time,
+    /// the loser left the game
+    Disconnect,
+    /// the same position came up `RuleSet::repetitions` times
+    Repetition,
+    /// no piece died for `RuleSet::no_progress_limit` moves
+    MoveLimit,
+    /// the last pieces of both sides died at once
+    MutualDestruction,
+}
+
+impl Reason {
+    pub fn all() -> [Reason; 6] {
+        [
+            Reason::Elimination,
+            Reason::Time,
+            Reason::Disconnect,
+            Reason::Repetition,
+            Reason::MoveLimit,
+            Reason::MutualDestruction,
+        ]
+    }
+
+    /// a short name, as used in game records and by the server
+    pub fn name(&self) -> &'static str {
+        match self {
+            Reason::Elimination => "elimination",
+            Reason::Time => "time",
+            Reason::Disconnect => "disconnect",
+            Reason::Repetition => "repetition",
+            Reason::MoveLimit => "move-limit",
+            Reason::MutualDestruction => "mutual-destruction",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Reason> {
+        Reason::all().into_iter().find(|r| r.name() == name)
+    }
+
+    /// whether the game is drawn when it ends like this
+    pub fn is_draw(&self) -> bool {
+        matches!(
+            self,
+            Reason::Repetition | Reason::MoveLimit | Reason::MutualDestruction
+        )
+    }
+}
+
+/**
+How a game ended: who won, if anyone, and why.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GameOutcome {
+    /// `None` for a draw
+    pub winner: Option<Player>,
+    pub reason: Reason,
+}
+
+impl GameOutcome {
+    pub fn win(winner: Player, reason: Reason) -> GameOutcome {
+        GameOutcome {
+            winner: Some(winner),
+            reason,
+        }
+    }
+
+    pub fn draw(reason: Reason) -> GameOutcome {
+        GameOutcome {
+            winner: None,
+            reason,
+        }
+    }
+
+    pub fn is_draw(&self) -> bool {
+        self.winner.is_none()
+    }
+
+    /// the result as written in game records: `1-0`, `0-1` or `1/2-1/2`
+    pub fn result(&self) -> &'static str {
+        match self.winner {
+            Some(Player::A) => "1-0",
+            Some(Player::B) => "0-1",
+            _ => "1/2-1/2",
+        }
+    }
+
+    /// the outcome written by `Display`
+    pub fn parse(text: &str) -> Option<GameOutcome> {
+        let (who, reason) = text.trim().split_once(' ')?;
+        let reason = Reason::parse(reason)?;
+        let winner = match who {
+            "A" => Some(Player::A),
+            "B" => Some(Player::B),
+            "draw" => None,
+            _ => return None,
+        };
+        (winner.is_none() == reason.is_draw()).then_some(GameOutcome { winner, reason })
+    }
+
+    /// what happened, in a few words, for the players
+    pub fn explanation(&self) -> String {
+        let loser = self.winner.map(|w| w.opponent());
+        match (self.reason, loser) {
+            (Reason::Elimination, Some(loser)) => format!("{:?} has no pieces left", loser),
+            (Reason::Time, Some(loser)) => format!("{:?} ran out of time", loser),
+            (Reason::Disconnect, Some(loser)) => format!("{:?} left the game", loser),
+            (Reason::Repetition, _) => "draw by repetition".to_string(),
+            (Reason::MoveLimit, _) => "draw: no piece was lost for too long".to_string(),
+            (Reason::MutualDestruction, _) => "draw: everyone died at once".to_string(),
+            (reason, None) => format!("draw by {}", reason.name()),
+        }
+    }
+}
+
+/// `A elimination`, `draw repetition`, ...
+impl fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.winner {
+            Some(winner) => write!(f, "{:?} {}", winner, self.reason.name()),
+            None => write!(f, "draw {}", self.reason.name()),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::game::notation::{GameRecord, NotationError, RecordedTurn};
+
+const MIN_SPEED: f32 = 0.125;
+const MAX_SPEED: f32 = 16.0;
+/// how long to wait before a move that was recorded without a time
+const DEFAULT_THINK_TIME: f32 = 1.0;
+
+/**
+Playback of a `GameRecord` in `GameState::Replay`.
+
+Each turn is played once its recorded think time has passed (scaled by
+`speed`), so the game unfolds with the same pacing and animations as when
+it was played.
+*/
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub record: GameRecord,
+    /// index into `record.turns` of the turn to be played next
+    pub next_turn: usize,
+    pub paused: bool,
+    pub speed: f32,
+    /// why the replay stopped, if its record went out of sync with the game
+    pub error: Option<NotationError>,
+    /// replay-seconds spent waiting on the next turn
+    progress: f32,
+    last_tick: f32,
+    /// how long a passed turn without a time took: the move timeout
+    pass_time: f32,
+}
+
+impl Replay {
+    pub fn new(record: GameRecord, time: f32) -> Replay {
+        let pass_time = record.rules().unwrap_or_default().play_move_timeout;
+        Replay {
+            pass_time,
+            record,
+            next_turn: 0,
+            paused: false,
+            speed: 1.0,
+            error: None,
+            progress: 0.0,
+            last_tick: time,
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (2.0 * self.speed).min(MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = (0.5 * self.speed).max(MIN_SPEED);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_turn >= self.record.turns.len()
+    }
+
+    pub fn peek(&self) -> Option<&RecordedTurn> {
+        self.record.turns.get(self.next_turn)
+    }
+
+    /**
+    moves replay time forward to `time`, and returns the next turn if it is due.
+    */
+    pub fn advance(&mut self, time: f32) -> Option<RecordedTurn> {
+        let dt = time - self.last_tick;
+        self.last_tick = time;
+        if self.paused || self.error.is_some() {
+            return None;
+        }
+        self.progress += self.speed * dt;
+        let turn = self.peek()?;
+        if self.progress >= self.think_time(turn) {
+            Some(turn.clone())
+        } else {
+            None
+        }
+    }
+
+    /// to be called whenever the turn pointed to by `next_turn` changes
+    pub fn on_turn_change(&mut self, time: f32) {
+        self.progress = 0.0;
+        self.last_tick = time;
+    }
+
+    fn think_time(&self, turn: &RecordedTurn) -> f32 {
+        match (&turn.notation, turn.think_time) {
+            (_, Some(t)) => t,
+            (Some(_), None) => DEFAULT_THINK_TIME,
+            (None, None) => self.pass_time,
+        }
+    }
+}
@@ -0,0 +1,389 @@
+/*!
+The mechanics of a turn, on a bare `Board`.
+
+The `GameController` plays turns through these, and so can anything that
+wants to look ahead without a controller (e.g. the AI searching on clones).
+*/
+use crate::config::CONF;
+use crate::engine::{
+    statuses::{Effect, Status, StatusType},
+    Board, Object, ObjectType, Player, RuleSet, TileType,
+};
+use crate::game::{
+    effect_queue::{EffectLog, EffectQueue, Origin},
+    moves::Move,
+    GameOutcome, Reason,
+};
+use crate::geometry::{HexCoord, ScreenCoord};
+use glam::Vec3;
+
+/**
+plays one turn: `r#move` (or a pass, if `None`) and whatever the objects on
+the board trigger on `move_nr`.
+
+returns the log of every effect that resolved.
+*/
+pub fn play_turn(
+    board: &mut Board,
+    r#move: Option<&Move>,
+    move_nr: usize,
+    time: f32,
+    move_duration: f32,
+    rules: &RuleSet,
+) -> EffectLog {
+    if let Some(m) = r#move {
+        move_to(board, &m.object, m.target(), time, move_duration);
+    }
+    // get object effects
+    let triggered_effects = tick_objects(board, move_nr, time, rules);
+    // run game logic to setup next move
+    let mut queue = EffectQueue::new();
+    if let Some(m) = r#move {
+        queue.extend(m.effects.iter().cloned(), Origin::Move);
+    }
+    queue.extend(triggered_effects, Origin::Object);
+    queue.resolve(time, |effect| {
+        apply_effect(board, effect, move_nr, time, rules)
+    })
+}
+
+/**
+- cleans up dead objects whose animations are over
+- resets expired animations to none
+*/
+pub fn remove_expired(board: &mut Board, time: f32) {
+    board.retain_statuses(
+        |s| !s.is_expired(time),
+        // the dead are kept for as long as something is still going on with them
+        |o| !o.props.dead || !o.statuses.is_empty(),
+    );
+}
+
+/**
+triggers whatever the objects have lined up for `move_nr`, and burns the
+pieces standing on lava.
+with `falling_tiles` off, tiles never do anything.
+*/
+pub fn tick_objects(board: &mut Board, move_nr: usize, time: f32, rules: &RuleSet) -> Vec<Effect> {
+    let mut effects = vec![];
+    board.update_objects(|o| {
+        if rules.falling_tiles || !o.is_tile() {
+            effects.extend(o.tick(move_nr, time));
+        }
+    });
+    effects.extend(burn(board, move_nr, rules));
+    effects
+}
+
+/**
+marks pieces that are on lava with the move they got there, and kills the
+ones that have been there for `RuleSet::lava_turns` moves.
+*/
+fn burn(board: &mut Board, move_nr: usize, rules: &RuleSet) -> Vec<Effect> {
+    let lava: Vec<HexCoord> = board
+        .tiles()
+        .into_iter()
+        .filter(|t| t.tile_type() == TileType::Lava && !t.props.dead)
+        .map(|t| t.coord)
+        .collect();
+    let mut effects = vec![];
+    board.update_objects(|piece| {
+        if piece.is_tile() || piece.props.dead || piece.otype == ObjectType::Wall {
+            return;
+        }
+        if !lava.contains(&piece.coord) {
+            piece.remove_status(&StatusType::Burning { move_nr: 0 });
+            return;
+        }
+        let since = piece.statuses.iter().find_map(|s| match s.stype {
+            StatusType::Burning { move_nr } => Some(move_nr),
+            _ => None,
+        });
+        match since {
+            Some(since) if move_nr >= since + rules.lava_turns => effects.push(Effect::Kill {
+                victim: piece.clone(),
+                killer: None,
+                animation_delay_frac: None,
+            }),
+            Some(_) => {}
+            None => piece.add_status(&Status::new(StatusType::Burning { move_nr })),
+        }
+    });
+    effects
+}
+
+pub fn move_to(board: &mut Board, object: &Object, to: &HexCoord, time: f32, duration: f32) {
+    if let Some(obj) = board.get_as_mut(object) {
+        let height = if obj.otype == ObjectType::Jumper {
+            0.5
+        } else {
+            0.0
+        };
+        obj.statuses.push(Status::new_move(
+            ScreenCoord::from_hexcoord(&obj.coord),
+            ScreenCoord::from_hexcoord(to),
+            time,
+            duration,
+            height,
+        ));
+        obj.set_coord(to);
+    }
+}
+
+/// how the game ends on `board`, if it does: with one side out of pieces, or both
+pub fn outcome(board: &Board) -> Option<GameOutcome> {
+    match winner(board)? {
+        Player::God => Some(GameOutcome::draw(Reason::MutualDestruction)),
+        player => Some(GameOutcome::win(player, Reason::Elimination)),
+    }
+}
+
+/// the side that is left with pieces; `Player::God` if neither is
+pub fn winner(board: &Board) -> Option<Player> {
+    let mut player_a_alive = false;
+    let mut player_b_alive = false;
+    for p in board.pieces().iter().filter(|p| !p.props.dead) {
+        if p.owned_by(&Player::A) {
+            player_a_alive = true;
+        }
+        if p.owned_by(&Player::B) {
+            player_b_alive = true;
+        }
+    }
+    match (player_a_alive, player_b_alive) {
+        (false, false) => Some(Player::God),
+        (true, false) => Some(Player::A),
+        (false, true) => Some(Player::B),
+        (true, true) => None,
+    }
+}
+
+/**
+applies `effects`, and everything they lead to, as if they came with a move.
+*/
+pub fn apply_effects(
+    board: &mut Board,
+    effects: &[Effect],
+    move_nr: usize,
+    time: f32,
+    rules: &RuleSet,
+) -> EffectLog {
+    let mut queue = EffectQueue::new();
+    queue.extend(effects.iter().cloned(), Origin::Move);
+    queue.resolve(time, |effect| {
+        apply_effect(board, effect, move_nr, time, rules)
+    })
+}
+
+/**
+applies one effect, and returns the effects it causes.
+*/
+fn apply_effect(
+    board: &mut Board,
+    effect: &Effect,
+    move_nr: usize,
+    time: f32,
+    rules: &RuleSet,
+) -> Vec<Effect> {
+    match effect {
+        Effect::Kill { victim, .. } => {
+            if let Some(v) = board.get_as_mut(victim).filter(|v| !v.props.dead) {
+                let status = effect.applying_status(time);
+                v.set_killed(status.as_ref());
+                return death_rattle(v).into_iter().collect();
+            }
+        }
+        Effect::KillAallOn { coord, .. } => {
+            let dying: Vec<Object> = board
+                .objects()
+                .into_iter()
+                .filter(|o| &o.coord == coord && !o.props.dead)
+                .cloned()
+                .collect();
+            let status = effect.applying_status(time);
+            board.kill_all_at(coord, status);
+            for tile in dying
+                .iter()
+                .filter(|o| o.tile_type() == TileType::Regrowing)
+            {
+                wither(board, tile, move_nr, rules);
+            }
+            return dying.iter().filter_map(death_rattle).collect();
+        }
+        Effect::Push {
+            object,
+            direction,
+            animation_delay_frac,
+        } => {
+            let delay = CONF.get().move_application_time * animation_delay_frac.unwrap_or(0.0);
+            return push(board, object, *direction, time + delay);
+        }
+        Effect::Arm { object, fuse } => {
+            if let Some(o) = board.get_as_mut(object).filter(|o| !o.props.dead) {
+                let detonation = Effect::Kill {
+                    victim: o.clone(),
+                    killer: None,
+                    animation_delay_frac: None,
+                };
+                o.add_status(&Status::new_delayed_effect(move_nr + fuse, detonation));
+                o.add_status(&Status {
+                    stype: CONF.get().bomber_fuse_indicator.clone(),
+                    start_time: Some(time),
+                    duration: None,
+                });
+            }
+        }
+        Effect::Detonate { bomber } => {
+            let center = bomber.coord;
+            return std::iter::once(center)
+                .chain(board.neighbours(&center, 1))
+                .filter_map(|c| board.contents(&c))
+                .map(|victim| Effect::Kill {
+                    victim: victim.clone(),
+                    killer: Some(bomber.clone()),
+                    animation_delay_frac: None,
+                })
+                .collect();
+        }
+        Effect::Teleport { object, to } => {
+            let free = board.tile_at(to).is_some_and(|t| !t.props.dead) && board.is_empty(to);
+            if let Some(o) = board.get_as_mut(object).filter(|o| !o.props.dead && free) {
+                // leaves once it has arrived on the teleporter
+                o.statuses.push(Status::new_move(
+                    o.get_screen_coord(),
+                    ScreenCoord::from_hexcoord(to),
+                    time + CONF.get().move_application_time,
+                    CONF.get().move_application_time,
+                    1.0,
+                ));
+                o.set_coord(to);
+            }
+        }
+        Effect::Regrow { tile, lifespan } => {
+            if let Some(old) = board.get_as_mut(tile).filter(|t| t.props.dead) {
+                let coord = old.coord;
+                let mut new = Object::new_tile(old.props.oid, coord, *lifespan, rules)
+                    .with_tile_type(TileType::Regrowing);
+                let at = ScreenCoord::from_hexcoord(&coord);
+                new.add_status(&Status::new_move(
+                    at.with_z(4.0),
+                    at,
+                    time,
+                    CONF.get().move_application_time,
+                    0.0,
+                ));
+                *old = new;
+            }
+        }
+        Effect::SetStatus { object, .. } => {
+            let status = effect.applying_status(time);
+            if let Some(o) = board.get_as_mut(object) {
+                o.add_status(&status.expect("SetStatus without a status"));
+            }
+        }
+        Effect::NoOp => {}
+    }
+    vec![]
+}
+
+/**
+a Regrowing tile that fell keeps falling until it comes back, on move
+`move_nr + RuleSet::regrow_turns`. it then stays as long as it was gone.
+*/
+fn wither(board: &mut Board, tile: &Object, move_nr: usize, rules: &RuleSet) {
+    let Some(t) = board.get_as_mut(tile) else {
+        return;
+    };
+    t.statuses
+        .iter_mut()
+        .filter(|s| s.stype == StatusType::Falling)
+        .for_each(|s| s.duration = None);
+    let back = move_nr + rules.regrow_turns;
+    let regrow = Effect::Regrow {
+        tile: t.clone(),
+        lifespan: back + rules.regrow_turns,
+    };
+    t.add_status(&Status::new_delayed_effect(back, regrow));
+}
+
+/**
+what happens when `piece` dies, however it dies: a Bomber goes off.
+*/
+fn death_rattle(piece: &Object) -> Option<Effect> {
+    match piece.otype {
+        ObjectType::Bomber => Some(Effect::Detonate {
+            bomber: piece.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/**
+the pieces that get shoved if `first` is pushed in `direction`: `first` and
+every live piece lined up behind it. `None` if the line ends in a wall, since
+nothing moves then.
+*/
+pub fn push_chain(board: &Board, first: &Object, direction: usize) -> Option<Vec<Object>> {
+    let mut chain = vec![];
+    let mut next = Some(first);
+    while let Some(piece) = next {
+        if piece.otype == ObjectType::Wall {
+            return None;
+        }
+        chain.push(piece.clone());
+        next = board
+            .neighbor(&piece.coord, direction, 1)
+            .and_then(|c| board.contents(&c));
+    }
+    Some(chain)
+}
+
+/**
+moves the chain starting at `object` one step in `direction`, far end first.
+pieces that are shoved off the board, or onto a missing or dead tile, fall.
+
+returns the death rattles of the pieces that fell.
+*/
+fn push(board: &mut Board, object: &Object, direction: usize, start_time: f32) -> Vec<Effect> {
+    let Some(first) = board.get_as_mut(object).filter(|o| !o.props.dead).cloned() else {
+        return vec![];
+    };
+    let Some(chain) = push_chain(board, &first, direction) else {
+        return vec![];
+    };
+    // the pusher came from behind `first`; the cell may be gone if the board
+    // was reshaped in the editor since the move was made
+    let Some(behind) = board.neighbor(&first.coord, (direction + 3) % 6, 1) else {
+        return vec![];
+    };
+    let mut rattles = vec![];
+    let step = first.get_screen_coord().as_vec() - ScreenCoord::from_hexcoord(&behind).as_vec();
+    let duration = CONF.get().move_application_time;
+    for piece in chain.iter().rev() {
+        let to = board.neighbor(&piece.coord, direction, 1);
+        let lands = to.is_some_and(|c| board.tile_at(&c).is_some_and(|t| !t.props.dead));
+        let Some(p) = board.get_as_mut(piece) else {
+            continue;
+        };
+        let status = match to {
+            Some(to) => {
+                p.set_coord(&to);
+                Status::new_pushed(-step, Vec3::ZERO, start_time, duration)
+            }
+            None => Status::new_pushed(Vec3::ZERO, step, start_time, duration),
+        };
+        // a piece that is pushed again before the last push is over skips ahead
+        p.statuses
+            .retain(|s| !matches!(s.stype, StatusType::Pushed { .. }));
+        p.add_status(&status);
+        if !lands {
+            p.set_killed(Some(&Status {
+                stype: StatusType::Falling,
+                start_time: Some(start_time + duration),
+                duration: Some(2.0),
+            }));
+            rattles.extend(death_rattle(p));
+        }
+    }
+    rattles
+}
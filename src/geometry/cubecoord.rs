@@ -0,0 +1,257 @@
+/*!
+Hex math in cube coordinates.
+
+A `HexCoord` is an axial coordinate: `q = x`, `r = y`. Adding `s = -q - r`
+makes it a cube coordinate, where distances, lines, rotations and
+reflections all come out simple. Cube coords can be negative and are not
+tied to a board; convert back with `to_hexcoord` to get onto one.
+*/
+use super::HexCoord;
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct CubeCoord {
+    pub q: i32,
+    pub r: i32,
+    pub s: i32,
+}
+
+impl CubeCoord {
+    /// the steps to the neighbours, in the same order as `HexCoord::get_neighbor` counts them
+    pub const DIRECTIONS: [CubeCoord; 6] = [
+        CubeCoord { q: 0, r: 1, s: -1 },
+        CubeCoord { q: -1, r: 1, s: 0 },
+        CubeCoord { q: -1, r: 0, s: 1 },
+        CubeCoord { q: 0, r: -1, s: 1 },
+        CubeCoord { q: 1, r: -1, s: 0 },
+        CubeCoord { q: 1, r: 0, s: -1 },
+    ];
+
+    /// from axial coordinates
+    pub fn new(q: i32, r: i32) -> CubeCoord {
+        CubeCoord { q, r, s: -q - r }
+    }
+
+    pub fn from_hexcoord(coord: &HexCoord) -> CubeCoord {
+        CubeCoord::new(coord.x as i32, coord.y as i32)
+    }
+
+    /// the coord on a `board_size` grid, if it is on it
+    pub fn to_hexcoord(&self, board_size: usize) -> Option<HexCoord> {
+        let on_grid = |v: i32| (0..board_size as i32).contains(&v);
+        (on_grid(self.q) && on_grid(self.r))
+            .then(|| HexCoord::new(self.q as usize, self.r as usize, board_size))
+    }
+
+    pub fn axial(&self) -> (i32, i32) {
+        (self.q, self.r)
+    }
+
+    /**
+    the nearest cube coord to a fractional one: round all three, then fix
+    the one that moved the most so they add up to 0 again.
+    */
+    pub fn round(q: f32, r: f32, s: f32) -> CubeCoord {
+        let (mut rq, mut rr, mut rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        } else {
+            rs = -rq - rr;
+        }
+        CubeCoord {
+            q: rq as i32,
+            r: rr as i32,
+            s: rs as i32,
+        }
+    }
+
+    /// steps from the origin
+    pub fn length(&self) -> i32 {
+        (self.q.abs() + self.r.abs() + self.s.abs()) / 2
+    }
+
+    pub fn distance(&self, other: &CubeCoord) -> i32 {
+        (*self - *other).length()
+    }
+
+    pub fn neighbor(&self, direction: usize) -> CubeCoord {
+        *self + CubeCoord::DIRECTIONS[direction]
+    }
+
+    pub fn neighbours(&self) -> [CubeCoord; 6] {
+        CubeCoord::DIRECTIONS.map(|d| *self + d)
+    }
+
+    /**
+    every coord on the straight line from `self` to `other`, both ends
+    included, one step apart. where the line runs exactly between two
+    coords, it keeps to the same side all the way.
+    */
+    pub fn line_to(&self, other: &CubeCoord) -> Vec<CubeCoord> {
+        let n = self.distance(other);
+        if n == 0 {
+            return vec![*self];
+        }
+        // both ends nudged the same way off the edges between coords, so ties
+        // are always broken to the same side
+        let nudge = |c: &CubeCoord| (c.q as f32 + 1e-4, c.r as f32 + 2e-4, c.s as f32 - 3e-4);
+        let ((q0, r0, s0), (q1, r1, s1)) = (nudge(self), nudge(other));
+        (0..=n)
+            .map(|i| {
+                let t = i as f32 / n as f32;
+                CubeCoord::round(q0 + (q1 - q0) * t, r0 + (r1 - r0) * t, s0 + (s1 - s0) * t)
+            })
+            .collect()
+    }
+
+    /// the coords exactly `radius` steps from `self`, going round the way the directions count
+    pub fn ring(&self, radius: usize) -> Vec<CubeCoord> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let mut curr = *self + CubeCoord::DIRECTIONS[4] * radius as i32;
+        let mut ring = Vec::with_capacity(6 * radius);
+        for direction in CubeCoord::DIRECTIONS {
+            for _ in 0..radius {
+                ring.push(curr);
+                curr = curr + direction;
+            }
+        }
+        ring
+    }
+
+    /// `self`, then the rings around it out to `radius`, from the inside out
+    pub fn spiral(&self, radius: usize) -> Vec<CubeCoord> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// every coord at most `radius` steps from `self`
+    pub fn range(&self, radius: usize) -> Vec<CubeCoord> {
+        let n = radius as i32;
+        (-n..=n)
+            .flat_map(|q| {
+                let lo = (-n).max(-q - n);
+                let hi = n.min(-q + n);
+                (lo..=hi).map(move |r| *self + CubeCoord::new(q, r))
+            })
+            .collect()
+    }
+
+    /// turned `steps` sixths of a turn around `centre`, the way the directions count
+    pub fn rotate(&self, centre: &CubeCoord, steps: i32) -> CubeCoord {
+        let mut v = *self - *centre;
+        for _ in 0..steps.rem_euclid(6) {
+            v = CubeCoord {
+                q: -v.r,
+                r: -v.s,
+                s: -v.q,
+            };
+        }
+        *centre + v
+    }
+
+    /// mirrored in the line through `centre` along which `q` stays the same
+    pub fn reflect_q(&self, centre: &CubeCoord) -> CubeCoord {
+        let v = *self - *centre;
+        *centre
+            + CubeCoord {
+                q: v.q,
+                r: v.s,
+                s: v.r,
+            }
+    }
+
+    /// mirrored in the line through `centre` along which `r` stays the same
+    pub fn reflect_r(&self, centre: &CubeCoord) -> CubeCoord {
+        let v = *self - *centre;
+        *centre
+            + CubeCoord {
+                q: v.s,
+                r: v.r,
+                s: v.q,
+            }
+    }
+
+    /// mirrored in the line through `centre` along which `s` stays the same
+    pub fn reflect_s(&self, centre: &CubeCoord) -> CubeCoord {
+        let v = *self - *centre;
+        *centre
+            + CubeCoord {
+                q: v.r,
+                r: v.q,
+                s: v.s,
+            }
+    }
+
+    /// mirrored through `centre`; the same as half a turn around it
+    pub fn reflect_point(&self, centre: &CubeCoord) -> CubeCoord {
+        *centre * 2 - *self
+    }
+
+    /**
+    tells if nothing `blocked` lies on the line between `self` and `other`.
+    the ends themselves may be blocked.
+    */
+    pub fn sees(&self, other: &CubeCoord, blocked: impl Fn(&CubeCoord) -> bool) -> bool {
+        let line = self.line_to(other);
+        line.iter()
+            .skip(1)
+            .take(line.len().saturating_sub(2))
+            .all(|c| !blocked(c))
+    }
+
+    /// the coords within `radius` that `self` sees (see `sees`)
+    pub fn field_of_view(
+        &self,
+        radius: usize,
+        blocked: impl Fn(&CubeCoord) -> bool,
+    ) -> Vec<CubeCoord> {
+        self.range(radius)
+            .into_iter()
+            .filter(|c| self.sees(c, &blocked))
+            .collect()
+    }
+}
+
+impl Add for CubeCoord {
+    type Output = CubeCoord;
+    fn add(self, other: CubeCoord) -> CubeCoord {
+        CubeCoord {
+            q: self.q + other.q,
+            r: self.r + other.r,
+            s: self.s + other.s,
+        }
+    }
+}
+
+impl Sub for CubeCoord {
+    type Output = CubeCoord;
+    fn sub(self, other: CubeCoord) -> CubeCoord {
+        self + -other
+    }
+}
+
+impl Neg for CubeCoord {
+    type Output = CubeCoord;
+    fn neg(self) -> CubeCoord {
+        CubeCoord {
+            q: -self.q,
+            r: -self.r,
+            s: -self.s,
+        }
+    }
+}
+
+impl Mul<i32> for CubeCoord {
+    type Output = CubeCoord;
+    fn mul(self, k: i32) -> CubeCoord {
+        CubeCoord {
+            q: k * self.q,
+            r: k * self.r,
+            s: k * self.s,
+        }
+    }
+}
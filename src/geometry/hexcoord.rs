@@ -1,3 +1,5 @@
+use super::CubeCoord;
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct HexCoord {
     pub x: usize,
@@ -13,10 +15,23 @@ impl HexCoord {
         HexCoord { x, y, board_size }
     }
 
+    pub fn cube(&self) -> CubeCoord {
+        CubeCoord::from_hexcoord(self)
+    }
+
+    /// steps between the two, as if the grid had no edges or holes
+    pub fn distance(&self, other: &HexCoord) -> usize {
+        self.cube().distance(&other.cube()) as usize
+    }
+
     pub fn get_all_directions(&self) -> Vec<usize> {
         vec![0, 1, 2, 3, 4, 5]
     }
 
+    /**
+    the coord `distance` steps away in `direction`, if it is on the grid.
+    whether there is a tile there is for the board's `Shape` to say.
+    */
     #[allow(clippy::identity_op)]
     pub fn get_neighbor(&self, direction: usize, distance: usize) -> Option<HexCoord> {
         /*
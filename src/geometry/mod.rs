@@ -1,5 +1,9 @@
 mod screencoord;
 mod hexcoord;
+mod cubecoord;
+mod shape;
 
 pub use screencoord::ScreenCoord;
 pub use hexcoord::HexCoord;
+pub use cubecoord::CubeCoord;
+pub use shape::Shape;
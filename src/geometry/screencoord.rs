@@ -1,5 +1,5 @@
 use super::HexCoord;
-use macroquad::prelude::*;
+use glam::{vec3, Vec3};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ScreenCoord {
@@ -13,19 +13,34 @@ impl ScreenCoord {
         ScreenCoord { x, y, z: 0.0 }
     }
 
+    /**
+    the middle of the grid goes to the origin, so any shape that sits in the
+    middle of its grid is centred (see `Shape::centring`).
+    */
     pub fn from_hexcoord(coord: &HexCoord) -> ScreenCoord {
-        let s = coord.board_size as f32;
-        let cx = coord.x as f32 - 0.5 * s;
-        let cy = coord.y as f32 - 0.5 * s;
-        let offset_x = 1.2 + cy;
-        let offset_y = 1.2;
-        let x = offset_x + (2.15 * cx);
-        let y = offset_y + (1.85 * cy);
+        let middle = 0.5 * (coord.board_size as f32 - 1.0);
+        ScreenCoord::on_grid(coord.x as f32 - middle, coord.y as f32 - middle)
+    }
 
-        let x = x - 0.6;
-        let y = y - 0.6;
+    /// where a point on the grid goes, with `(0, 0)` at the origin; `x` and `y` need not be whole
+    pub fn on_grid(x: f32, y: f32) -> ScreenCoord {
+        ScreenCoord {
+            x: 2.15 * x + y,
+            y: 1.85 * y,
+            z: 0.0,
+        }
+    }
 
-        ScreenCoord { x, y, z: 0.0 }
+    /**
+    the coord whose tile would be under this position, on a board of the given size.
+    */
+    pub fn to_hexcoord(&self, board_size: usize) -> Option<HexCoord> {
+        (0..board_size)
+            .flat_map(|x| (0..board_size).map(move |y| HexCoord::new(x, y, board_size)))
+            .map(|c| (c, self.dist_from(&ScreenCoord::from_hexcoord(&c))))
+            .filter(|(_, d)| *d < 1.0)
+            .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+            .map(|(c, _)| c)
     }
 
     pub fn dist_from(&self, other: &ScreenCoord) -> f32 {
@@ -0,0 +1,140 @@
+use super::{CubeCoord, HexCoord, ScreenCoord};
+use std::collections::HashSet;
+
+/**
+The cells a board is made of: where it has tiles, or had them before they fell.
+
+Cells are `HexCoord`s on a `size` by `size` grid, but they need not fill it:
+a hexagon, a ring or a board with holes all fit in one. Neighbours are only
+found among the cells, so nothing moves through a hole or off the edge.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Shape {
+    size: usize,
+    cells: HashSet<HexCoord>,
+}
+
+impl Shape {
+    pub fn new(size: usize, cells: impl IntoIterator<Item = HexCoord>) -> Shape {
+        Shape {
+            size,
+            cells: cells.into_iter().collect(),
+        }
+    }
+
+    /// the whole `size` by `size` grid
+    pub fn rhombus(size: usize) -> Shape {
+        let cells = (0..size).flat_map(|x| (0..size).map(move |y| HexCoord::new(x, y, size)));
+        Shape::new(size, cells)
+    }
+
+    /// every cell at most `radius` steps from the middle of a `2 * radius + 1` grid
+    pub fn hexagon(radius: usize) -> Shape {
+        let size = 2 * radius + 1;
+        let middle = CubeCoord::new(radius as i32, radius as i32);
+        let cells = middle
+            .range(radius)
+            .into_iter()
+            .filter_map(|c| c.to_hexcoord(size));
+        Shape::new(size, cells)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn contains(&self, coord: &HexCoord) -> bool {
+        self.cells.contains(coord)
+    }
+
+    pub fn insert(&mut self, coord: HexCoord) {
+        self.cells.insert(coord);
+    }
+
+    /// the cells, row by row
+    pub fn cells(&self) -> Vec<HexCoord> {
+        let mut cells: Vec<HexCoord> = self.cells.iter().copied().collect();
+        cells.sort_by_key(|c| (c.y, c.x));
+        cells
+    }
+
+    /// the cell `distance` steps away in `direction`, if there is one
+    pub fn neighbor(
+        &self,
+        coord: &HexCoord,
+        direction: usize,
+        distance: usize,
+    ) -> Option<HexCoord> {
+        coord
+            .get_neighbor(direction, distance)
+            .filter(|c| self.contains(c))
+    }
+
+    pub fn neighbours(&self, coord: &HexCoord, distance: usize) -> Vec<HexCoord> {
+        coord
+            .get_all_directions()
+            .into_iter()
+            .filter_map(|d| self.neighbor(coord, d, distance))
+            .collect()
+    }
+
+    /// the cells at most `radius` steps from `coord`, as the crow flies
+    pub fn within(&self, coord: &HexCoord, radius: usize) -> Vec<HexCoord> {
+        coord
+            .cube()
+            .range(radius)
+            .into_iter()
+            .filter_map(|c| c.to_hexcoord(self.size))
+            .filter(|c| self.contains(c))
+            .collect()
+    }
+
+    /**
+    where to put the shape so it is centred on screen: the grid size (at most
+    `max_size`) and how far to shift every cell along `x` and `y`.
+
+    `ScreenCoord::from_hexcoord` puts the middle of the grid at the origin, so
+    the shape is moved (and the grid grown, if need be) to have the middle of
+    its outline as close to the middle of the grid as whole steps allow.
+    */
+    pub fn centring(&self, max_size: usize) -> (usize, i32, i32) {
+        if self.cells.is_empty() {
+            return (self.size, 0, 0);
+        }
+        let xs = self.cells.iter().map(|c| c.x as i32);
+        let ys = self.cells.iter().map(|c| c.y as i32);
+        let (min_x, max_x) = (xs.clone().min().unwrap(), xs.max().unwrap());
+        let (min_y, max_y) = (ys.clone().min().unwrap(), ys.max().unwrap());
+        let screen: Vec<ScreenCoord> = self
+            .cells
+            .iter()
+            .map(|c| ScreenCoord::on_grid(c.x as f32, c.y as f32))
+            .collect();
+        let (left, right) = screen
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(l, r), s| (l.min(s.x), r.max(s.x)));
+        let (top, bottom) = screen
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(t, b), s| (t.min(s.y), b.max(s.y)));
+        let middle = ScreenCoord::new(0.5 * (left + right), 0.5 * (top + bottom));
+
+        let needed = (max_x - min_x).max(max_y - min_y) as usize + 1;
+        let mut best = (f32::MAX, self.size, 0, 0);
+        for size in needed..=max_size.min(needed + 2).max(needed) {
+            let m = 0.5 * (size as f32 - 1.0);
+            let grid_middle = ScreenCoord::on_grid(m, m);
+            for dx in -min_x..=size as i32 - 1 - max_x {
+                for dy in -min_y..=size as i32 - 1 - max_y {
+                    let shift = ScreenCoord::on_grid(dx as f32, dy as f32);
+                    let error = middle.add(&shift).dist_from(&grid_middle);
+                    // on a tie, the smaller grid and the placement nearest the corner win
+                    if error < best.0 - 1e-3 {
+                        best = (error, size, dx, dy);
+                    }
+                }
+            }
+        }
+        let (_, size, dx, dy) = best;
+        (size, dx, dy)
+    }
+}
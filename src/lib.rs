@@ -1,5 +1,9 @@
+pub mod ai;
 pub mod config;
 pub mod engine;
 pub mod game;
 pub mod geometry;
+pub mod net;
+pub mod rng;
+#[cfg(feature = "ui")]
 pub mod ui;
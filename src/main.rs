@@ -1,6 +1,13 @@
-use hexaroni::config::CONF;
-use hexaroni::engine::statuses::StatusType;
-use hexaroni::game::GameController;
+use hexaroni::ai::{Bot, Evaluation, Mcts, Minimax};
+use hexaroni::config::{self, ConfigSources, CONF};
+use hexaroni::engine::{statuses::StatusType, Board, Player, RuleSet};
+use hexaroni::game::{
+    editor::EditTool,
+    generator::Generator,
+    notation::{GameRecord, MoveNotation, RecordedTurn},
+    Clock, GameController, GameState, WallClock,
+};
+use hexaroni::net::{Client, ProtocolError, ServerMessage, DEFAULT_PORT};
 use hexaroni::ui::{
     control::{ControlStatus, KbdAction, MouseAction},
     rendering::Renderer,
@@ -8,7 +15,16 @@ use hexaroni::ui::{
 };
 use macroquad::prelude::*;
 use miniquad::window::screen_size;
-use std::time::Instant;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// where a game that is still on is saved on quitting
+const SAVE_FILE: &str = "hexaroni.save";
+/// edited maps are saved as this, followed by `.map` or, if that is taken, `-2.map`, ...
+const EDITED_MAP: &str = "maps/edited";
 
 fn window_conf() -> Conf {
     Conf {
@@ -21,14 +37,32 @@ fn window_conf() -> Conf {
 
 #[macroquad::main(window_conf)]
 async fn main() {
-    let mut game = GameController::new();
+    load_config();
+    let mut rules = rules_from_args();
+    let mut board = board_from_args(&rules);
+    let replay_record = replay_record_from_args();
+    let clock = WallClock::new();
+    let mut client = client_from_args().map(|(client, online_board, online_rules)| {
+        board = online_board;
+        rules = online_rules;
+        client
+    });
+    let saved_record = saved_record_from_args();
+    if let Some(record) = &saved_record {
+        // resetting starts the saved game over
+        board = record.board().unwrap_or(board);
+        rules = record.rules().unwrap_or(rules);
+    }
+    let mut game = match &saved_record {
+        Some(record) => resumed_game(record, &clock),
+        None => new_game(&board, &rules, &replay_record, &clock),
+    };
+    game.networked = client.is_some();
     let mut control_status = ControlStatus::default();
     let mut renderer = Renderer::new().unwrap();
     let mut curr_window_size = screen_size();
-    let camera_up = CONF.camera_up;
-    let camera_target = CONF.camera_target;
-    let mut camera_position = CONF.camera_position;
-    let start_time = Instant::now();
+    let mut camera_position = CONF.get().camera_position;
+    let mut edited_map = None;
 
     loop {
         // recreate shader on resize
@@ -41,48 +75,163 @@ async fn main() {
         }
 
         // update control, camera, and game state
-        let curr_time = start_time.elapsed().as_secs_f32();
-        game.tick(curr_time);
+        let curr_time = clock.now();
+        game.tick(&clock);
+        if let Some(client) = &client {
+            for message in client.poll() {
+                handle_server_message(&mut game, message, &clock);
+            }
+        }
         camera_position = control_camera(&camera_position);
-        let camera = renderer.create_camera(camera_position, camera_target, camera_up);
+        let camera = renderer.create_camera(
+            camera_position,
+            CONF.get().camera_target,
+            CONF.get().camera_up,
+        );
         control_status.update(&game, &camera);
 
         // handle events
-        match &control_status.action {
-            MouseAction::Dragging => {
-                if control_status.dragging.is_none() {
+        if let GameState::Editing = game.game_state {
+            edit_board(&mut game, &control_status);
+        } else {
+            match &control_status.action {
+                MouseAction::Dragging if control_status.dragging.is_none() => {
                     // if we are not dragging already, we set the hovered object to dragging
                     if let Some(ref hovered) = control_status.hovering {
                         if hovered.props.draggable
                             && hovered.owned_by(&game.current_player())
+                            && !game.is_computer(game.current_player())
+                            && client
+                                .as_ref()
+                                .is_none_or(|c| c.player == game.current_player())
                             && !hovered.props.dead
                         {
                             control_status.dragging = Some(Drag::create(hovered, &mut game));
                         }
                     }
                 }
-            }
-            MouseAction::Drop => {
-                if let Some(drag) = &control_status.dragging {
-                    game.board
-                        .get_as_mut(&drag.object)
-                        .unwrap()
-                        .remove_status(&StatusType::Dragged);
-                    if let Some(target_tile) = &control_status.targeting {
-                        if let Some(r#move) = drag.get_move_to(&target_tile.coord) {
-                            game.apply_move(r#move, curr_time, CONF.move_application_time);
+                MouseAction::Drop => {
+                    if let Some(drag) = &control_status.dragging {
+                        game.board
+                            .get_as_mut(&drag.object)
+                            .unwrap()
+                            .remove_status(&StatusType::Dragged);
+                        if let Some(target_tile) = &control_status.targeting {
+                            if let Some(r#move) = drag.get_move_to(&target_tile.coord) {
+                                match &mut client {
+                                    // the server decides, and tells us what happened
+                                    Some(client) => {
+                                        if let Err(e) = client.send_move(r#move) {
+                                            println!("unable to send move: {}", e);
+                                        }
+                                    }
+                                    None => game.apply_move(
+                                        r#move,
+                                        &clock,
+                                        CONF.get().move_application_time,
+                                    ),
+                                }
+                            }
                         }
                     }
+                    control_status.dragging = None;
                 }
-                control_status.dragging = None;
+                _ => {}
             }
-            _ => {}
         }
 
-        match get_event() {
-            Some(KbdAction::StartGame) => game.start_game(),
-            Some(KbdAction::Reset) => game = GameController::new(),
-            Some(KbdAction::Quit) => break,
+        let event = get_event().filter(|e| client.is_none() || allowed_online(e));
+        match event {
+            Some(KbdAction::StartGame) => {
+                if let GameState::Editing = game.game_state {
+                    if game.finish_editing().is_ok() {
+                        board = game.board.clone();
+                        game.start_game(&clock);
+                    }
+                } else {
+                    game.start_game(&clock);
+                }
+            }
+            Some(KbdAction::ToggleEditor) => {
+                if let GameState::Editing = game.game_state {
+                    if game.finish_editing().is_ok() {
+                        board = game.board.clone();
+                    }
+                } else {
+                    game.start_editing();
+                }
+            }
+            Some(KbdAction::EditorTool(i)) => {
+                if let Some(editor) = &mut game.editor {
+                    editor.tool = EditTool::ALL[i];
+                }
+            }
+            Some(KbdAction::EditorSwapPlayer) => {
+                if let Some(editor) = &mut game.editor {
+                    editor.swap_player();
+                }
+            }
+            Some(KbdAction::EditorLifespanUp) => {
+                if let Some(editor) = &mut game.editor {
+                    editor.lifespan_up();
+                }
+            }
+            Some(KbdAction::EditorLifespanDown) => {
+                if let Some(editor) = &mut game.editor {
+                    editor.lifespan_down();
+                }
+            }
+            Some(KbdAction::SaveMap) => {
+                if let GameState::Editing = game.game_state {
+                    // the loaded map is left as it was; saving again overwrites the new one
+                    let path = edited_map.get_or_insert_with(new_map_path);
+                    save_map(&mut game, path);
+                }
+            }
+            Some(KbdAction::Reset) => game = new_game(&board, &rules, &replay_record, &clock),
+            Some(KbdAction::NextRules) => {
+                if let GameState::Waiting = game.game_state {
+                    rules = game.rules.next_preset();
+                    game.set_rules(rules);
+                }
+            }
+            Some(KbdAction::Undo) => {
+                control_status.dragging = None;
+                game.undo(&clock);
+            }
+            Some(KbdAction::Redo) => {
+                control_status.dragging = None;
+                game.redo(&clock);
+            }
+            Some(KbdAction::ReplayPause) => {
+                if let Some(replay) = &mut game.replay {
+                    replay.toggle_pause();
+                }
+            }
+            Some(KbdAction::ReplayFaster) => {
+                if let Some(replay) = &mut game.replay {
+                    replay.faster();
+                }
+            }
+            Some(KbdAction::ReplaySlower) => {
+                if let Some(replay) = &mut game.replay {
+                    replay.slower();
+                }
+            }
+            Some(KbdAction::PrintRecord) => println!("{}", GameRecord::from_controller(&game)),
+            Some(KbdAction::Quit) => {
+                if client.is_none() && matches!(game.game_state, GameState::Playing { .. }) {
+                    save_game(&game, &clock, Path::new(SAVE_FILE));
+                }
+                break;
+            }
+            Some(KbdAction::ReloadConfig) => match config::reload() {
+                Ok(()) => {
+                    println!("config reloaded");
+                    camera_position = CONF.get().camera_position;
+                }
+                Err(e) => println!("config not reloaded: {}", e),
+            },
             Some(KbdAction::ReloadShader) => {
                 match Renderer::new() {
                     Ok(r) => renderer = r,
@@ -104,15 +253,360 @@ fn get_event() -> Option<KbdAction> {
     if is_key_pressed(KeyCode::Space) {
         return Some(KbdAction::ReloadShader);
     }
+    if is_key_pressed(KeyCode::C) {
+        return Some(KbdAction::ReloadConfig);
+    }
     if is_key_pressed(KeyCode::Enter) {
         return Some(KbdAction::StartGame);
     }
     if is_key_pressed(KeyCode::R) {
         return Some(KbdAction::Reset);
     }
+    if is_key_pressed(KeyCode::V) {
+        return Some(KbdAction::NextRules);
+    }
+    if is_key_pressed(KeyCode::Z) {
+        return Some(KbdAction::Undo);
+    }
+    if is_key_pressed(KeyCode::Y) {
+        return Some(KbdAction::Redo);
+    }
+    if is_key_pressed(KeyCode::P) {
+        return Some(KbdAction::PrintRecord);
+    }
+    if is_key_pressed(KeyCode::E) {
+        return Some(KbdAction::ToggleEditor);
+    }
+    let tool_keys = [
+        KeyCode::Key1,
+        KeyCode::Key2,
+        KeyCode::Key3,
+        KeyCode::Key4,
+        KeyCode::Key5,
+        KeyCode::Key6,
+        KeyCode::Key7,
+    ];
+    if let Some(i) = tool_keys.iter().position(|k| is_key_pressed(*k)) {
+        return Some(KbdAction::EditorTool(i));
+    }
+    if is_key_pressed(KeyCode::Tab) {
+        return Some(KbdAction::EditorSwapPlayer);
+    }
+    if is_key_pressed(KeyCode::RightBracket) {
+        return Some(KbdAction::EditorLifespanUp);
+    }
+    if is_key_pressed(KeyCode::LeftBracket) {
+        return Some(KbdAction::EditorLifespanDown);
+    }
+    if is_key_pressed(KeyCode::S) {
+        return Some(KbdAction::SaveMap);
+    }
+    if is_key_pressed(KeyCode::K) {
+        return Some(KbdAction::ReplayPause);
+    }
+    if is_key_pressed(KeyCode::Equal) {
+        return Some(KbdAction::ReplayFaster);
+    }
+    if is_key_pressed(KeyCode::Minus) {
+        return Some(KbdAction::ReplaySlower);
+    }
     None
 }
 
+/// online, the game can't be edited, reset or taken back
+fn allowed_online(action: &KbdAction) -> bool {
+    matches!(
+        action,
+        KbdAction::Quit
+            | KbdAction::ReloadShader
+            | KbdAction::ReloadConfig
+            | KbdAction::PrintRecord
+    )
+}
+
+fn handle_server_message(
+    game: &mut GameController,
+    message: Result<ServerMessage, ProtocolError>,
+    clock: &impl Clock,
+) {
+    match message {
+        Ok(ServerMessage::Rules(rules)) => game.set_rules(rules),
+        Ok(ServerMessage::Start) => game.start_game(clock),
+        Ok(ServerMessage::Turn {
+            notation,
+            think_time,
+            ..
+        }) => {
+            let turn = notation
+                .map(|n| MoveNotation::parse(&n, game.board.size))
+                .transpose()
+                .map(|notation| RecordedTurn {
+                    notation,
+                    think_time: Some(think_time),
+                });
+            if let Err(e) = turn.and_then(|turn| game.play_remote_turn(&turn, clock)) {
+                println!("out of sync with the server: {}", e);
+            }
+        }
+        Ok(ServerMessage::GameOver { outcome }) => {
+            game.game_state = GameState::GameOver { outcome };
+        }
+        Ok(ServerMessage::Rejected(reason)) => println!("move rejected: {}", reason),
+        Ok(ServerMessage::Welcome { .. }) => {}
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// left click uses the editor's tool, right click erases
+fn edit_board(game: &mut GameController, control_status: &ControlStatus) {
+    let coord = control_status
+        .mouse_pos
+        .and_then(|pos| pos.to_hexcoord(game.board.size));
+    if let (Some(editor), Some(coord)) = (game.editor.clone(), coord) {
+        if is_mouse_button_pressed(MouseButton::Left) {
+            editor.apply(&mut game.board, &coord, &game.rules);
+        } else if is_mouse_button_pressed(MouseButton::Right) {
+            editor.erase(&mut game.board, &coord);
+        }
+    }
+}
+
+/// saves the edited board, if it is playable
+fn save_map(game: &mut GameController, path: &Path) {
+    let message = match game.board.verify() {
+        Ok(()) => match game.board.save(path) {
+            Ok(()) => format!("saved to {}", path.display()),
+            Err(e) => e.to_string(),
+        },
+        Err(e) => e.to_string(),
+    };
+    println!("{}", message);
+    if let Some(editor) = &mut game.editor {
+        editor.message = Some(message);
+    }
+}
+
+/// the first of `maps/edited.map`, `maps/edited-2.map`, ... that doesn't exist yet
+fn new_map_path() -> PathBuf {
+    (1..)
+        .map(|n| match n {
+            1 => PathBuf::from(format!("{}.map", EDITED_MAP)),
+            n => PathBuf::from(format!("{}-{}.map", EDITED_MAP, n)),
+        })
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+fn save_game(game: &GameController, clock: &impl Clock, path: &Path) {
+    match fs::write(path, game.save(clock).to_string()) {
+        Ok(()) => println!(
+            "game saved to {}; carry on with --resume {}",
+            path.display(),
+            path.display()
+        ),
+        Err(e) => eprintln!("unable to save game to {}: {}", path.display(), e),
+    }
+}
+
+/// `hexaroni [--config <file>] [--set <key>=<value>]...`; see `config::file`
+fn load_config() {
+    let args: Vec<String> = env::args().collect();
+    if let Err(e) = ConfigSources::from_args(&args).and_then(config::load) {
+        eprintln!("unable to load config: {}", e);
+        process::exit(1);
+    }
+}
+
+/// the value following `flag` on the command line
+fn arg_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    args.iter().position(|a| a == flag).map(|i| {
+        args.get(i + 1).cloned().unwrap_or_else(|| {
+            eprintln!("{} needs a value", flag);
+            process::exit(1);
+        })
+    })
+}
+
+/**
+`hexaroni --map <map>` plays on the given map instead of the test board, and
+`hexaroni --generate <seed>` on a board made up from the seed.
+*/
+fn board_from_args(rules: &RuleSet) -> Board {
+    if let Some(seed) = arg_value("--generate") {
+        return generated_board(&seed, rules);
+    }
+    match arg_value("--map") {
+        Some(path) => Board::load(Path::new(&path)).unwrap_or_else(|e| {
+            eprintln!("unable to load map {}: {}", path, e);
+            process::exit(1);
+        }),
+        None => Board::test_square(),
+    }
+}
+
+fn generated_board(seed: &str, rules: &RuleSet) -> Board {
+    let board = seed
+        .parse::<u64>()
+        .ok()
+        .and_then(|s| Generator::default().generate(s, rules));
+    board.unwrap_or_else(|| {
+        eprintln!("unable to generate a board from seed {}", seed);
+        process::exit(1);
+    })
+}
+
+/// `hexaroni --replay <record>` replays a recorded game
+fn replay_record_from_args() -> Option<GameRecord> {
+    arg_value("--replay").map(|path| read_record(&path))
+}
+
+/// `hexaroni --resume <save>` carries on with a game saved on quitting
+fn saved_record_from_args() -> Option<GameRecord> {
+    arg_value("--resume").map(|path| read_record(&path))
+}
+
+fn read_record(path: &str) -> GameRecord {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("unable to read {}: {}", path, e);
+        process::exit(1);
+    });
+    GameRecord::parse(&text).unwrap_or_else(|e| {
+        eprintln!("unable to parse {}: {}", path, e);
+        process::exit(1);
+    })
+}
+
+/// `hexaroni --rules <preset>` picks the rules to start out with
+fn rules_from_args() -> RuleSet {
+    match arg_value("--rules") {
+        Some(name) => RuleSet::preset(&name).unwrap_or_else(|| {
+            let names: Vec<&str> = RuleSet::presets().iter().map(|r| r.name).collect();
+            eprintln!("unknown rules: {} (try {})", name, names.join(", "));
+            process::exit(1);
+        }),
+        None => RuleSet::default(),
+    }
+}
+
+/// `hexaroni --connect <host[:port]>` plays on a server instead of locally
+fn client_from_args() -> Option<(Client, Board, RuleSet)> {
+    let addr = arg_value("--connect")?;
+    let addr = match addr.contains(':') {
+        true => addr,
+        false => format!("{}:{}", addr, DEFAULT_PORT),
+    };
+    match Client::connect(&addr) {
+        Ok((client, board, rules)) => {
+            println!(
+                "connected to {}, playing {:?} by {} rules",
+                addr, client.player, rules.name
+            );
+            Some((client, board, rules))
+        }
+        Err(e) => {
+            eprintln!("unable to connect to {}: {}", addr, e);
+            process::exit(1);
+        }
+    }
+}
+
+/// `hexaroni --computer A|B|AB` lets the computer play for those players
+fn computers_from_args() -> Vec<Player> {
+    let Some(players) = arg_value("--computer") else {
+        return vec![];
+    };
+    players
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'A' => Player::A,
+            'B' => Player::B,
+            _ => {
+                eprintln!("--computer takes A, B or AB, not {}", players);
+                process::exit(1);
+            }
+        })
+        .collect()
+}
+
+/**
+`hexaroni --seed <seed>` starts the randomness of the game from the seed;
+with `--generate <seed>`, that seed does for both.
+*/
+fn seed_from_args() -> Option<u64> {
+    let seed = arg_value("--seed").or_else(|| arg_value("--generate"))?;
+    Some(seed.parse().unwrap_or_else(|_| {
+        eprintln!("bad seed: {}", seed);
+        process::exit(1);
+    }))
+}
+
+/**
+`hexaroni --bot minimax|mcts` picks the kind of computer player.
+
+in a game from a given seed, MCTS searches for a set number of playouts
+rather than for a time, so the game can be played again from the seed.
+*/
+fn bot_from_args(seeded: bool) -> Box<dyn Bot> {
+    match arg_value("--bot").as_deref() {
+        None | Some("minimax") => {
+            Box::new(Minimax::new(CONF.get().ai_depth, Evaluation::default()))
+        }
+        Some("mcts") => {
+            // reseeded by the game before every move
+            let mut mcts = Mcts::new(0);
+            if seeded && mcts.iterations.is_none() {
+                mcts.iterations = Some(CONF.get().mcts_seeded_iterations);
+            }
+            Box::new(mcts)
+        }
+        Some(other) => {
+            eprintln!("unknown bot: {} (try minimax or mcts)", other);
+            process::exit(1);
+        }
+    }
+}
+
+fn new_game(
+    board: &Board,
+    rules: &RuleSet,
+    replay_record: &Option<GameRecord>,
+    clock: &impl Clock,
+) -> GameController {
+    match replay_record {
+        Some(record) => GameController::new_replay(record.clone(), clock).unwrap_or_else(|e| {
+            eprintln!("unable to replay game: {}", e);
+            process::exit(1);
+        }),
+        None => {
+            let mut game = GameController::with_board(board.clone());
+            game.set_rules(*rules);
+            let seed = seed_from_args();
+            if let Some(seed) = seed {
+                game.set_seed(seed);
+            }
+            for player in computers_from_args() {
+                game.set_computer(player, bot_from_args(seed.is_some()));
+            }
+            game.bots_in_background = true;
+            game
+        }
+    }
+}
+
+fn resumed_game(record: &GameRecord, clock: &impl Clock) -> GameController {
+    let mut game = GameController::resume(record, clock).unwrap_or_else(|e| {
+        eprintln!("unable to resume game: {}", e);
+        process::exit(1);
+    });
+    for player in computers_from_args() {
+        // it carries on from the seed in the record
+        game.set_computer(player, bot_from_args(true));
+    }
+    game.bots_in_background = true;
+    game
+}
+
 fn control_camera(camera_position: &Vec3) -> Vec3 {
     let mut camera_position = *camera_position;
     if is_key_down(KeyCode::LeftAlt) {
@@ -0,0 +1,99 @@
+use crate::engine::{Board, Player, RuleSet};
+use crate::game::{
+    moves::Move,
+    notation::{setup_to_board, MoveNotation},
+};
+use crate::net::{ClientMessage, ProtocolError, ServerMessage};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/**
+A connection to a `Server`.
+
+Messages are read on a background thread, so that `poll` never blocks the
+game loop. Dropping the client closes the connection.
+*/
+pub struct Client {
+    pub player: Player,
+    stream: TcpStream,
+    incoming: Receiver<Result<ServerMessage, ProtocolError>>,
+}
+
+impl Client {
+    /**
+    connects, and waits to be told which player we are, what the board is,
+    and what the rules are.
+    */
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<(Client, Board, RuleSet)> {
+        let stream = TcpStream::connect(addr)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let (player, board) = match ServerMessage::parse(&line) {
+            Ok(ServerMessage::Welcome { player, setup }) => {
+                let board = setup_to_board(&setup).map_err(|e| invalid(e.to_string()))?;
+                (player, board)
+            }
+            Ok(other) => return Err(invalid(format!("expected a welcome, got '{}'", other))),
+            Err(e) => return Err(invalid(e.to_string())),
+        };
+        line.clear();
+        reader.read_line(&mut line)?;
+        let rules = match ServerMessage::parse(&line) {
+            Ok(ServerMessage::Rules(rules)) => rules,
+            Ok(other) => return Err(invalid(format!("expected rules, got '{}'", other))),
+            Err(e) => return Err(invalid(e.to_string())),
+        };
+
+        let (messages, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let message = match line {
+                    Ok(line) => ServerMessage::parse(&line),
+                    Err(e) => Err(ProtocolError(e.to_string())),
+                };
+                if messages.send(message).is_err() {
+                    return;
+                }
+            }
+            let _ = messages.send(Err(ProtocolError(
+                "server closed the connection".to_string(),
+            )));
+        });
+
+        Ok((
+            Client {
+                player,
+                stream,
+                incoming,
+            },
+            board,
+            rules,
+        ))
+    }
+
+    pub fn send_move(&mut self, r#move: &Move) -> io::Result<()> {
+        let message = ClientMessage::Move(MoveNotation::from_move(r#move).to_string());
+        writeln!(self.stream, "{}", message)
+    }
+
+    /// every message that has arrived since the last poll
+    pub fn poll(&self) -> Vec<Result<ServerMessage, ProtocolError>> {
+        self.incoming.try_iter().collect()
+    }
+
+    /// waits for the next message; `None` once the connection is gone
+    pub fn wait(&self) -> Option<Result<ServerMessage, ProtocolError>> {
+        self.incoming.recv().ok()
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        // the reading thread has a handle on the socket too
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
@@ -0,0 +1,17 @@
+/*!
+Playing over the network.
+
+The `Server` owns the only real `GameController`: it checks every move, runs
+the move timer, and tells both players what happened. Each `Client` keeps a
+copy of the game that only changes when the server says so.
+*/
+
+mod client;
+mod protocol;
+mod server;
+
+pub use client::Client;
+pub use protocol::{ClientMessage, ProtocolError, ServerMessage};
+pub use server::Server;
+
+pub const DEFAULT_PORT: u16 = 7878;
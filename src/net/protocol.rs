@@ -0,0 +1,149 @@
+//! The wire format: one message per line, in plain text.
+//!
+//! ```text
+//! server -> client
+//! welcome A size 7|tiles|...|pieces|...   you play A, on this board
+//! rules blitz starting_player=A ...       by these rules
+//! start                                   the countdown has started
+//! turn A Db2-b6xb5 2.10                   A played a move, after 2.10s
+//! turn B -- 5.01                          B's time ran out
+//! rejected not your turn                  your last move was not played
+//! over A elimination                      A won, as B has no pieces left
+//! over draw repetition                    nobody won, and why
+//!
+//! client -> server
+//! move Db2-b6xb5
+//! ```
+//!
+//! Moves are written in the notation of `game::notation`, boards as a
+//! `Setup` header, and rules as `RuleSet`s are displayed.
+
+use crate::engine::{Player, RuleSet};
+use crate::game::GameOutcome;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    Welcome {
+        player: Player,
+        setup: String,
+    },
+    Rules(RuleSet),
+    Start,
+    Turn {
+        player: Player,
+        /// `None` if the turn was passed
+        notation: Option<String>,
+        think_time: f32,
+    },
+    Rejected(String),
+    GameOver {
+        outcome: GameOutcome,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    Move(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtocolError(pub String);
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerMessage::Welcome { player, setup } => write!(f, "welcome {:?} {}", player, setup),
+            ServerMessage::Rules(rules) => write!(f, "rules {}", rules),
+            ServerMessage::Start => write!(f, "start"),
+            ServerMessage::Turn {
+                player,
+                notation,
+                think_time,
+            } => write!(
+                f,
+                "turn {:?} {} {:.2}",
+                player,
+                notation.as_deref().unwrap_or("--"),
+                think_time
+            ),
+            ServerMessage::Rejected(reason) => write!(f, "rejected {}", reason),
+            ServerMessage::GameOver { outcome } => write!(f, "over {}", outcome),
+        }
+    }
+}
+
+impl ServerMessage {
+    pub fn parse(line: &str) -> Result<ServerMessage, ProtocolError> {
+        let line = line.trim();
+        let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match keyword {
+            "welcome" => {
+                let (player, setup) = rest.split_once(' ').ok_or_else(|| bad(line))?;
+                Ok(ServerMessage::Welcome {
+                    player: parse_player(player)?,
+                    setup: setup.to_string(),
+                })
+            }
+            "rules" => Ok(ServerMessage::Rules(
+                RuleSet::parse(rest).map_err(ProtocolError)?,
+            )),
+            "start" => Ok(ServerMessage::Start),
+            "turn" => {
+                let parts: Vec<&str> = rest.split_whitespace().collect();
+                let [player, notation, think_time] = parts[..] else {
+                    return Err(bad(line));
+                };
+                Ok(ServerMessage::Turn {
+                    player: parse_player(player)?,
+                    notation: Some(notation.to_string()).filter(|n| n != "--"),
+                    think_time: think_time.parse().map_err(|_| bad(line))?,
+                })
+            }
+            "rejected" => Ok(ServerMessage::Rejected(rest.to_string())),
+            "over" => Ok(ServerMessage::GameOver {
+                outcome: GameOutcome::parse(rest).ok_or_else(|| bad(line))?,
+            }),
+            _ => Err(bad(line)),
+        }
+    }
+}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientMessage::Move(notation) => write!(f, "move {}", notation),
+        }
+    }
+}
+
+impl ClientMessage {
+    pub fn parse(line: &str) -> Result<ClientMessage, ProtocolError> {
+        let line = line.trim();
+        match line.split_once(' ') {
+            Some(("move", notation)) => Ok(ClientMessage::Move(notation.trim().to_string())),
+            _ => Err(bad(line)),
+        }
+    }
+}
+
+fn parse_player(text: &str) -> Result<Player, ProtocolError> {
+    match text.trim() {
+        "A" => Ok(Player::A),
+        "B" => Ok(Player::B),
+        "God" => Ok(Player::God),
+        other => Err(ProtocolError(format!("unknown player: '{}'", other))),
+    }
+}
+
+fn bad(line: &str) -> ProtocolError {
+    ProtocolError(format!("bad message: '{}'", line))
+}
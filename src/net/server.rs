@@ -0,0 +1,197 @@
+use crate::config::CONF;
+use crate::engine::{Board, Player, RuleSet};
+use crate::game::{
+    notation::{setup_to_string, MoveNotation},
+    GameController, GameOutcome, GameState, Reason, WallClock,
+};
+use crate::net::{ClientMessage, ProtocolError, ServerMessage};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::{thread, time::Duration};
+
+/// how long the server sleeps between ticks
+const TICK: Duration = Duration::from_millis(5);
+
+enum Event {
+    Message(Player, Result<ClientMessage, ProtocolError>),
+    Disconnected(Player),
+}
+
+/**
+Hosts games between two players over TCP.
+
+The first player to connect plays A, the second B.
+*/
+pub struct Server {
+    listener: TcpListener,
+    board: Board,
+    rules: RuleSet,
+}
+
+impl Server {
+    pub fn bind(addr: impl ToSocketAddrs, board: Board, rules: RuleSet) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(addr)?,
+            board,
+            rules,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /**
+    waits for two players, and plays a game between them.
+    returns how it ended.
+    */
+    pub fn play_game(&self) -> io::Result<GameOutcome> {
+        let (events, incoming) = mpsc::channel();
+        let mut streams = HashMap::new();
+        for player in [Player::A, Player::B] {
+            let (mut stream, addr) = self.listener.accept()?;
+            println!("{:?} connected from {}", player, addr);
+            send(
+                &mut stream,
+                &ServerMessage::Welcome {
+                    player,
+                    setup: setup_to_string(&self.board),
+                },
+            )?;
+            send(&mut stream, &ServerMessage::Rules(self.rules))?;
+            listen(player, stream.try_clone()?, events.clone());
+            streams.insert(player, stream);
+        }
+        let clock = WallClock::new();
+        let mut game = GameController::with_board(self.board.clone());
+        game.set_rules(self.rules);
+        game.start_game(&clock);
+        broadcast(&mut streams, &ServerMessage::Start);
+        self.run(&mut game, &clock, &mut streams, &incoming)
+    }
+
+    fn run(
+        &self,
+        game: &mut GameController,
+        clock: &WallClock,
+        streams: &mut HashMap<Player, TcpStream>,
+        incoming: &Receiver<Event>,
+    ) -> io::Result<GameOutcome> {
+        let mut turns_sent = 0;
+        loop {
+            while let Ok(event) = incoming.try_recv() {
+                match event {
+                    Event::Message(player, Ok(ClientMessage::Move(text))) => {
+                        if let Err(reason) = Server::play(game, clock, player, &text) {
+                            reject(game, streams, player, reason);
+                        }
+                    }
+                    Event::Message(player, Err(e)) => {
+                        reject(game, streams, player, e.to_string());
+                    }
+                    Event::Disconnected(player) => {
+                        println!("{:?} disconnected", player);
+                        disconnect(game, streams, player);
+                    }
+                }
+            }
+
+            // passes the turn of whoever runs out of time
+            game.tick(clock);
+
+            for turn in game.history.records().skip(turns_sent) {
+                let message = ServerMessage::Turn {
+                    player: turn.player,
+                    notation: turn
+                        .r#move
+                        .as_ref()
+                        .map(|m| MoveNotation::from_move(m).to_string()),
+                    think_time: turn.think_time,
+                };
+                broadcast(streams, &message);
+            }
+            turns_sent = game.history.len();
+
+            if let GameState::GameOver { outcome } = game.game_state {
+                broadcast(streams, &ServerMessage::GameOver { outcome });
+                return Ok(outcome);
+            }
+            thread::sleep(TICK);
+        }
+    }
+
+    /// checks a move sent by `player`, and plays it
+    fn play(
+        game: &mut GameController,
+        clock: &WallClock,
+        player: Player,
+        text: &str,
+    ) -> Result<(), String> {
+        if !game.game_state.allows_moves() || game.current_player() != player {
+            return Err("not your turn".to_string());
+        }
+        let r#move = MoveNotation::parse(text, game.board.size)
+            .and_then(|n| n.resolve(&game.board, &player, &game.rules))
+            .map_err(|e| e.to_string())?;
+        game.apply_move(&r#move, clock, CONF.get().move_application_time);
+        Ok(())
+    }
+}
+
+/// reads messages from `player` on a thread of its own
+fn listen(player: Player, stream: TcpStream, events: Sender<Event>) {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message = ClientMessage::parse(&line);
+            if events.send(Event::Message(player, message)).is_err() {
+                return;
+            }
+        }
+        let _ = events.send(Event::Disconnected(player));
+    });
+}
+
+/// tells `player` their message was rejected; if that fails, they are gone
+fn reject(
+    game: &mut GameController,
+    streams: &mut HashMap<Player, TcpStream>,
+    player: Player,
+    reason: String,
+) {
+    let Some(stream) = streams.get_mut(&player) else {
+        return;
+    };
+    if let Err(e) = send(stream, &ServerMessage::Rejected(reason)) {
+        println!("unable to reach {:?}: {}", player, e);
+        disconnect(game, streams, player);
+    }
+}
+
+/// `player` is gone, and loses unless the game is over already
+fn disconnect(game: &mut GameController, streams: &mut HashMap<Player, TcpStream>, player: Player) {
+    streams.remove(&player);
+    if game.game_state.outcome().is_none() {
+        game.game_state = GameState::GameOver {
+            outcome: GameOutcome::win(player.opponent(), Reason::Disconnect),
+        };
+    }
+}
+
+fn send(stream: &mut TcpStream, message: &ServerMessage) -> io::Result<()> {
+    writeln!(stream, "{}", message)
+}
+
+/// sends to everyone still connected; losing someone is noticed by their listener
+fn broadcast(streams: &mut HashMap<Player, TcpStream>, message: &ServerMessage) {
+    for stream in streams.values_mut() {
+        let _ = send(stream, message);
+    }
+}
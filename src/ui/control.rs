@@ -15,8 +15,22 @@ pub enum MouseAction {
 pub enum KbdAction {
     Quit,
     ReloadShader,
+    ReloadConfig,
     StartGame,
     Reset,
+    NextRules,
+    Undo,
+    Redo,
+    PrintRecord,
+    ReplayPause,
+    ReplayFaster,
+    ReplaySlower,
+    ToggleEditor,
+    EditorTool(usize),
+    EditorSwapPlayer,
+    EditorLifespanUp,
+    EditorLifespanDown,
+    SaveMap,
 }
 
 #[derive(Debug, Clone)]
@@ -26,7 +26,7 @@ impl Drag {
             .get_as_mut(object)
             .unwrap()
             .add_status(&Status::new_dragged());
-        let moves = legal_moves(object, &game.board);
+        let moves = legal_moves(object, &game.board, &game.rules);
         Drag {
             object: object.clone(),
             targets: moves.iter().map(|m| *m.target()).collect(),
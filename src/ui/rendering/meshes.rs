@@ -1,4 +1,5 @@
-use crate::engine::Object;
+use crate::config::CONF;
+use crate::engine::{Object, TileType};
 use crate::ui::rendering::{transforms, Renderable};
 use itertools::izip;
 use macroquad::prelude::*;
@@ -57,12 +58,25 @@ pub fn hud_quad(x0: f32, y0: f32, x1: f32, y1: f32) -> Mesh {
     }
 }
 
+/**
+`color` is tinted by the tile type. lava pulses, and a teleporter glows from
+its middle.
+*/
 pub fn tile_hex_mesh(tile: &Object, color: &Vec4, as_highlighted: bool, time: f32) -> Renderable {
     let model_matrix = transforms::create_model_matrix(tile, time);
     let size = tile.props.size;
     let d = 0.86602;
     let thickness = 0.2 * size;
     let glow = if as_highlighted { 1.0 } else { 0.0 };
+    let pulse = 0.5 + 0.5 * (3.0 * time).sin();
+    let (tint, center_glow) = match tile.tile_type() {
+        TileType::Ground => (Vec4::ZERO, glow),
+        TileType::Ice => (CONF.get().tile_ice_color, glow),
+        TileType::Lava => ((0.7 + 0.3 * pulse) * CONF.get().tile_lava_color, glow),
+        TileType::Teleporter { .. } => (CONF.get().tile_teleporter_color, pulse.max(glow)),
+        TileType::Regrowing => (CONF.get().tile_regrowing_color, glow),
+    };
+    let color = &(*color + tint);
 
     let position = model_matrix.project_point3(vec3(0.0, 0.0, 0.0));
     let offsets = [
@@ -78,7 +92,7 @@ pub fn tile_hex_mesh(tile: &Object, color: &Vec4, as_highlighted: bool, time: f3
         position,
         uv: vec2(0.0, 1.0),
         color: Color::from_vec(*color).into(),
-        normal: vec3(0.0, 0.0, -1.0).normalize().extend(glow),
+        normal: vec3(0.0, 0.0, -1.0).normalize().extend(center_glow),
     }];
     let corner_vertices: Vec<Vertex> = corner_positions
         .iter()
@@ -284,6 +298,143 @@ pub fn obj_dasher_mesh(
     }
 }
 
+pub fn obj_pusher_mesh(
+    object: &Object,
+    object_color: &Vec4,
+    player_color: &Vec4,
+    as_active: bool,
+    time: f32,
+) -> Renderable {
+    let model_matrix = transforms::create_model_matrix(object, time);
+    let size = object.props.size;
+    let r = 0.45;
+    let h = 0.5;
+
+    let position = model_matrix.project_point3(vec3(0.0, 0.0, 0.0));
+    let offsets: Vec<Vec3> = (0..6)
+        .map(|i| {
+            let angle = std::f32::consts::FRAC_PI_3 * i as f32;
+            vec3(r * angle.cos(), r * angle.sin(), 0.0)
+        })
+        .collect();
+
+    let bottom_vertices: Vec<Vertex> = offsets
+        .iter()
+        .map(|o| Vertex {
+            position: model_matrix.project_point3(size * (*o)),
+            uv: vec2(0.0, 0.0),
+            normal: o.extend(0.0).normalize(),
+            color: Color::from_vec(*object_color).into(),
+        })
+        .collect();
+    let top_vertices: Vec<Vertex> = offsets
+        .iter()
+        .map(|o| Vertex {
+            position: model_matrix.project_point3(size * (0.7 * *o).with_z(-h)),
+            uv: vec2(if as_active { 0.1 } else { 0.0 }, 0.0),
+            normal: vec4(o.x, o.y, -h, 1.0).normalize(),
+            color: Color::from_vec(*object_color).into(),
+        })
+        .collect();
+    let top_vertex = [Vertex {
+        position: model_matrix.project_point3(size * vec3(0.0, 0.0, -h)),
+        normal: vec4(0.0, 0.0, -1.0, 1.0).normalize(),
+        uv: vec2(if as_active { 1.0 } else { 0.6 }, 0.0),
+        color: Color::from_vec(*object_color).into(),
+    }];
+
+    let vertices = bottom_vertices
+        .iter()
+        .chain(&top_vertices)
+        .chain(&top_vertex)
+        .copied()
+        .collect();
+    // the sides are quads between the bottom and the top ring, the top a fan
+    let indices = (0..6u16)
+        .flat_map(|i| {
+            let j = (i + 1) % 6;
+            [i, j, 6 + i, j, 6 + j, 6 + i, 6 + i, 6 + j, 12]
+        })
+        .collect();
+    let texture = texture_from_2_colors(object_color, player_color);
+
+    Renderable {
+        mesh: Mesh {
+            vertices,
+            indices,
+            texture: Some(texture),
+        },
+        position,
+    }
+}
+
+pub fn obj_bomber_mesh(
+    object: &Object,
+    object_color: &Vec4,
+    player_color: &Vec4,
+    as_active: bool,
+    time: f32,
+) -> Renderable {
+    let model_matrix = transforms::create_model_matrix(object, time);
+    let size = object.props.size;
+    let r = 0.35;
+    let h = 0.4;
+
+    let position = model_matrix.project_point3(vec3(0.0, 0.0, 0.0));
+    let offsets: Vec<Vec3> = (0..6)
+        .map(|i| {
+            let angle = std::f32::consts::FRAC_PI_3 * (i as f32 + 0.5);
+            vec3(r * angle.cos(), r * angle.sin(), -h)
+        })
+        .collect();
+
+    let bottom_vertex = [Vertex {
+        position: model_matrix.project_point3(vec3(0.0, 0.0, 0.0)),
+        uv: vec2(0.0, 0.0),
+        normal: vec4(0.0, 0.0, 1.0, 0.0),
+        color: Color::from_vec(*object_color).into(),
+    }];
+    let middle_vertices: Vec<Vertex> = offsets
+        .iter()
+        .map(|o| Vertex {
+            position: model_matrix.project_point3(size * (*o)),
+            uv: vec2(if as_active { 0.1 } else { 0.0 }, 0.0),
+            normal: o.with_z(0.0).extend(0.0).normalize(),
+            color: Color::from_vec(*object_color).into(),
+        })
+        .collect();
+    let top_vertex = [Vertex {
+        position: model_matrix.project_point3(size * vec3(0.0, 0.0, -2.0 * h)),
+        normal: vec4(0.0, 0.0, -1.0, 1.0).normalize(),
+        uv: vec2(if as_active { 1.0 } else { 0.6 }, 0.0),
+        color: Color::from_vec(*object_color).into(),
+    }];
+
+    let vertices = bottom_vertex
+        .iter()
+        .chain(&middle_vertices)
+        .chain(&top_vertex)
+        .copied()
+        .collect();
+    // two hexagonal pyramids, base to base
+    let indices = (1..7u16)
+        .flat_map(|i| {
+            let j = i % 6 + 1;
+            [0, j, i, i, j, 7]
+        })
+        .collect();
+    let texture = texture_from_2_colors(object_color, player_color);
+
+    Renderable {
+        mesh: Mesh {
+            vertices,
+            indices,
+            texture: Some(texture),
+        },
+        position,
+    }
+}
+
 fn texture_from_2_colors(color_a: &Vec4, color_b: &Vec4) -> Texture2D {
     let col_a = color_to_bytes(color_a);
     let col_b = color_to_bytes(color_b);
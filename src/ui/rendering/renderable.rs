@@ -10,32 +10,64 @@ pub struct Renderable {
 }
 
 impl Renderable {
-    pub fn from_tile(tile: &Object, control_status: &ControlStatus, time: f32) -> Renderable {
+    /**
+    `blast` is how strongly the tile still glows from a detonation, from 0 to 1.
+    */
+    pub fn from_tile(
+        tile: &Object,
+        control_status: &ControlStatus,
+        blast: f32,
+        time: f32,
+    ) -> Renderable {
         let mut as_highlighted = false;
-        let mut color = CONF.tile_base_color;
+        let mut color = CONF.get().tile_base_color + blast * CONF.get().tile_blast_color;
         if let Some(drag) = &control_status.dragging {
             if drag.object.coord == tile.coord {
-                color += CONF.tile_dragged_from_color;
+                color += CONF.get().tile_dragged_from_color;
                 as_highlighted = true;
             }
         } else if let Some(tgt) = &control_status.targeting {
             if tgt == tile {
-                color += CONF.tile_targeted_color;
+                color += CONF.get().tile_targeted_color;
                 as_highlighted = true;
             }
         }
         if let Some(drag) = &control_status.dragging {
             if drag.has_move_to(&tile.coord) {
-                color += CONF.tile_possible_move_color;
+                color += CONF.get().tile_possible_move_color;
                 as_highlighted = true;
             }
         }
         meshes::tile_hex_mesh(tile, &color, as_highlighted, time)
     }
 
+    /**
+    tiles in the map editor are shaded by how long they live,
+    and highlighted when under the cursor.
+    */
+    pub fn from_edited_tile(
+        tile: &Object,
+        max_lifespan: usize,
+        control_status: &ControlStatus,
+        time: f32,
+    ) -> Renderable {
+        let lifespan = tile.lifespan().unwrap_or(max_lifespan);
+        let mut color = CONF.get().tile_base_color
+            + (lifespan as f32 / max_lifespan as f32) * CONF.get().tile_lifespan_color;
+        let hovered = control_status
+            .mouse_pos
+            .and_then(|pos| pos.to_hexcoord(tile.coord.board_size));
+        let as_highlighted = hovered == Some(tile.coord);
+        if as_highlighted {
+            color += CONF.get().tile_targeted_color;
+        }
+        meshes::tile_hex_mesh(tile, &color, as_highlighted, time)
+    }
+
     pub fn from_object(object: &Object, as_active: bool, time: f32) -> Renderable {
-        let player_color = CONF.player_color.get(&object.player).unwrap();
-        let object_color = CONF.object_color.get(&object.otype).unwrap();
+        let conf = CONF.get();
+        let player_color = conf.player_color.get(&object.player).unwrap();
+        let object_color = conf.object_color.get(&object.otype).unwrap();
 
         match object.otype {
             ObjectType::Wall => meshes::obj_wall_mesh(object, player_color, player_color, time),
@@ -45,6 +77,12 @@ impl Renderable {
             ObjectType::Jumper => {
                 meshes::obj_jumper_mesh(object, object_color, player_color, as_active, time)
             }
+            ObjectType::Pusher => {
+                meshes::obj_pusher_mesh(object, object_color, player_color, as_active, time)
+            }
+            ObjectType::Bomber => {
+                meshes::obj_bomber_mesh(object, object_color, player_color, as_active, time)
+            }
             _ => panic!("bad thing happen"),
         }
     }
@@ -1,6 +1,10 @@
 use crate::config::CONF;
-use crate::engine::Player;
-use crate::game::{GameController, GameState};
+use crate::engine::{Player, RuleSet, TimeControl};
+use crate::game::{
+    editor::{EditTool, Editor},
+    replay::Replay,
+    GameController, GameOutcome, GameState,
+};
 use crate::ui::rendering::meshes::hud_quad;
 use crate::ui::{control::ControlStatus, rendering::Renderable};
 use itertools::Itertools;
@@ -8,7 +12,6 @@ use macroquad::prelude::*;
 use macroquad::Error;
 use miniquad::window::screen_size;
 use miniquad::{CullFace, PipelineParams};
-use std::time::Instant;
 use std::{env, fs};
 
 pub struct Renderer {
@@ -22,7 +25,7 @@ pub struct Renderer {
 
 impl Renderer {
     pub fn new() -> Result<Renderer, Error> {
-        let render_scale = CONF.render_scale;
+        let render_scale = CONF.get().render_scale;
         let render_target = Renderer::create_target(render_scale);
         Ok(Renderer {
             render_scale,
@@ -120,9 +123,10 @@ impl Renderer {
             ..
         } = game.game_state
         {
-            // render hud
-            let time_remaining = CONF.play_move_timeout - move_start.elapsed().as_secs_f32();
-            let frac_remaining = time_remaining / CONF.play_move_timeout;
+            // render hud: what is left of the time the player had when the move started
+            let time_remaining = game.time_left(current_player, time).unwrap_or_default();
+            let time_at_start = game.time_left(current_player, move_start);
+            let frac_remaining = time_remaining / time_at_start.unwrap_or(1.0).max(f32::EPSILON);
             let flipped: f32 = if current_player == Player::A { 0. } else { 1. };
             gl_use_material(&self.hud_material);
             self.hud_material.set_uniform("canvas_size", screen_size());
@@ -133,7 +137,7 @@ impl Renderer {
             self.hud_material.set_uniform("flipped", flipped);
             self.hud_material.set_uniform(
                 "fill_color",
-                CONF.player_color.get(&current_player).unwrap().xyz(),
+                CONF.get().player_color.get(&current_player).unwrap().xyz(),
             );
             let mesh = hud_quad(0.85, 0.85, 0.98, 0.98);
             draw_mesh(&mesh);
@@ -144,16 +148,25 @@ impl Renderer {
         // status text
         match game.game_state {
             GameState::Waiting => {
-                Renderer::render_waiting(time);
+                Renderer::render_waiting(&game.rules, time);
             }
-            GameState::GameOver { winner } => {
-                Renderer::render_win(&winner, time);
+            GameState::GameOver { outcome } => {
+                Renderer::render_win(&outcome, time);
             }
             GameState::Countdown { started_at } => {
-                Renderer::render_countdown(&started_at, CONF.game_start_countdown, time);
+                Renderer::render_countdown(started_at, CONF.get().game_start_countdown, time);
+            }
+            GameState::Playing { .. } if game.rules.time_control != TimeControl::PerMove => {
+                Renderer::render_clocks(game, time);
             }
             _ => {}
         }
+        if let Some(replay) = &game.replay {
+            Renderer::render_replay(replay, time);
+        }
+        if let Some(editor) = &game.editor {
+            Renderer::render_editor(editor, time);
+        }
     }
 
     fn render_game(
@@ -162,11 +175,37 @@ impl Renderer {
         control_status: &ControlStatus,
         time: f32,
     ) {
+        let max_lifespan = game
+            .board
+            .tiles()
+            .iter()
+            .filter_map(|t| t.lifespan())
+            .max()
+            .unwrap_or(1);
+        let (blasted, blast) = match game.last_effects() {
+            Some(log) => {
+                let fade = (time - log.time) / (2.0 * CONF.get().kill_duration);
+                (log.blasted(), (1.0 - fade).clamp(0.0, 1.0))
+            }
+            None => (vec![], 0.0),
+        };
         let tile_renderables: Vec<Renderable> = game
             .board
             .tiles()
             .iter()
-            .map(|t| Renderable::from_tile(t, control_status, time))
+            .map(|t| match game.game_state {
+                GameState::Editing => {
+                    Renderable::from_edited_tile(t, max_lifespan, control_status, time)
+                }
+                _ => {
+                    let blast = if blasted.contains(&t.coord) {
+                        blast
+                    } else {
+                        0.0
+                    };
+                    Renderable::from_tile(t, control_status, blast, time)
+                }
+            })
             .collect();
         let piece_renderables: Vec<Renderable> = game
             .board
@@ -189,22 +228,92 @@ impl Renderer {
             .for_each(|renderable| draw_mesh(&renderable.mesh));
     }
 
-    fn render_win(winner: &Player, _time: f32) {
-        let text = format!("{:?} rocks!", &winner);
+    fn render_win(outcome: &GameOutcome, _time: f32) {
+        let text = match outcome.winner {
+            Some(winner) => format!("{:?} rocks!", winner),
+            None => "Draw!".to_string(),
+        };
         let (w, h) = screen_size();
         let text_width = 0.8 * h;
         draw_text(&text, 0.15 * (w - text_width), 0.5 * h, 0.5 * h, ORANGE);
+        let reason = outcome.explanation();
+        let reason_width = measure_text(&reason, None, (0.08 * h) as u16, 1.0).width;
+        draw_text(
+            &reason,
+            0.5 * (w - reason_width),
+            0.65 * h,
+            0.08 * h,
+            ORANGE,
+        );
+    }
+
+    /// both players' clocks, next to the hud timer
+    fn render_clocks(game: &GameController, time: f32) {
+        let (w, h) = screen_size();
+        for (i, player) in [Player::A, Player::B].into_iter().enumerate() {
+            let Some(left) = game.time_left(player, time) else {
+                continue;
+            };
+            let left = left.max(0.0);
+            let mut text = format!("{:?} {}:{:04.1}", player, (left / 60.0) as u32, left % 60.0);
+            match game.clocks.get(&player) {
+                Some(bank) if bank.flagged => text.push_str(" flag"),
+                Some(bank) if bank.periods > 0 && bank.main <= 0.0 => {
+                    text.push_str(&format!(" ({})", bank.periods))
+                }
+                _ => {}
+            }
+            let color = Color::from_vec(*CONF.get().player_color.get(&player).unwrap());
+            let y = (0.9 + 0.06 * i as f32) * h;
+            draw_text(&text, 0.83 * w - 0.4 * h, y, 0.06 * h, color);
+        }
+    }
+
+    fn render_replay(replay: &Replay, _time: f32) {
+        let text = if let Some(error) = &replay.error {
+            format!("replay stopped, out of sync with its record: {}", error)
+        } else if replay.paused {
+            "replay (paused)".to_string()
+        } else {
+            format!("replay x{}", replay.speed)
+        };
+        let (_, h) = screen_size();
+        draw_text(&text, 0.02 * h, 0.06 * h, 0.06 * h, ORANGE);
+    }
+
+    fn render_editor(editor: &Editor, _time: f32) {
+        let tool = match editor.tool {
+            EditTool::Tile => "tile".to_string(),
+            EditTool::Wall => "wall".to_string(),
+            EditTool::Dasher => format!("dasher {:?}", editor.player),
+            EditTool::Jumper => format!("jumper {:?}", editor.player),
+            EditTool::Pusher => format!("pusher {:?}", editor.player),
+            EditTool::Bomber => format!("bomber {:?}", editor.player),
+            EditTool::Lifespan => "lifespan".to_string(),
+        };
+        let lines = [
+            "editing: 1-7 tool, tab player, [ ] lifespan, s save, e done".to_string(),
+            format!("tool: {}   lifespan: {}", tool, editor.lifespan),
+            editor.message.clone().unwrap_or_default(),
+        ];
+        let (_, h) = screen_size();
+        for (i, line) in lines.iter().enumerate() {
+            let y = (0.06 + 0.05 * i as f32) * h;
+            draw_text(line, 0.02 * h, y, 0.05 * h, ORANGE);
+        }
     }
 
-    fn render_waiting(_time: f32) {
+    fn render_waiting(rules: &RuleSet, _time: f32) {
         let text = "Press enter to play.";
         let (w, h) = screen_size();
         let text_width = 1.2 * h;
         draw_text(text, 0.25 * (w - text_width), 0.5 * h, 0.15 * h, ORANGE);
+        let rules_text = format!("rules: {} (v to change)", rules.name);
+        draw_text(&rules_text, 0.02 * h, 0.06 * h, 0.06 * h, ORANGE);
     }
 
-    fn render_countdown(started_at: &Instant, duration: f32, _time: f32) {
-        let remaining = duration - started_at.elapsed().as_secs_f32();
+    fn render_countdown(started_at: f32, duration: f32, time: f32) {
+        let remaining = duration - (time - started_at);
         let scale = 1.0 * (1.0 - remaining + remaining.trunc());
         let text = format!("{}", remaining as i32);
         let (w, h) = screen_size();
@@ -17,7 +17,8 @@ pub fn create_model_matrix(object: &Object, time: f32) -> Mat4 {
             StatusType::Wobble { .. } => 0,
             StatusType::Killed { .. } => 1,
             StatusType::Move { .. } => 1,
-            StatusType::Falling { .. } => 1,
+            StatusType::Pushed { .. } => 1,
+            StatusType::Falling => 1,
             _ => -1,
         }
     }
@@ -72,10 +73,17 @@ fn status_matrix(status: &Status, time: f32) -> Mat4 {
             let jump = progress * (progress - 1.0) * vec3(0.0, 0.0, height) * (dst - src).length();
             Mat4::from_translation(target + jump)
         }
+        StatusType::Pushed { from, to } => {
+            let start_time = status.start_time.expect("pushed without start_time");
+            let duration = status.duration.expect("pushed without duration");
+            let progress = ((time - start_time) / duration).clamp(0.0, 1.0);
+            Mat4::from_translation(from + progress * (to - from))
+        }
         StatusType::Falling => {
             let start_time = status.start_time.expect("falling without start_time");
             const DOWN: Vec3 = vec3(0.0, 0.0, 1.0);
-            let t = time - start_time;
+            // a piece can be set to fall once it has been pushed off its tile
+            let t = (time - start_time).max(0.0);
             let g = 2.7;
             Mat4::from_translation(g * DOWN * t * t)
         }
@@ -0,0 +1,163 @@
+mod common;
+
+use common::{board, MAPS};
+use hexaroni::ai::Position;
+use hexaroni::engine::{Board, Object, ObjectType, Player, RuleSet};
+use hexaroni::geometry::HexCoord;
+use hexaroni::rng::Rng;
+
+fn coords(board: &Board) -> Vec<HexCoord> {
+    (0..board.size)
+        .flat_map(|x| (0..board.size).map(move |y| HexCoord::new(x, y, board.size)))
+        .collect()
+}
+
+/// every lookup the board answers through its index, against a look through all objects
+fn assert_lookups_match_scan(board: &Board) {
+    let objects = board.objects();
+    for coord in coords(board) {
+        let on = |o: &&&Object| o.coord == coord;
+        let tile = objects.iter().filter(on).find(|o| o.is_tile()).copied();
+        let piece = objects.iter().filter(on).find(|o| !o.is_tile()).copied();
+        let contents = objects
+            .iter()
+            .filter(on)
+            .find(|o| !o.is_tile() && !o.props.dead)
+            .copied();
+        assert_eq!(board.tile_at(&coord), tile, "tile_at {:?}", coord);
+        assert_eq!(board.piece_at(&coord), piece, "piece_at {:?}", coord);
+        assert_eq!(board.contents(&coord), contents, "contents {:?}", coord);
+    }
+    for o in &objects {
+        let first = objects.iter().find(|p| p.props.oid == o.props.oid).copied();
+        assert_eq!(board.get(o.props.oid), first, "get {}", o.props.oid);
+    }
+}
+
+/// the hash of the same objects, put on a board from scratch; false if they don't make a board
+fn hash_as_if_new(board: &Board) -> bool {
+    let rules = RuleSet::default();
+    let objects = board.objects().into_iter().cloned().collect();
+    let Ok(fresh) = Board::new(board.size, objects) else {
+        return false;
+    };
+    for (move_nr, to_move) in [(0, Player::A), (7, Player::B)] {
+        assert_eq!(
+            board.hash(move_nr, to_move, &rules),
+            fresh.hash(move_nr, to_move, &rules)
+        );
+    }
+    true
+}
+
+#[test]
+fn lookups_follow_objects_moved_through_get_as_mut() {
+    for map in MAPS {
+        let mut board = board(map);
+        let mut rng = Rng::new(1);
+        let pieces: Vec<Object> = board.pieces().into_iter().cloned().collect();
+        let free: Vec<HexCoord> = coords(&board)
+            .into_iter()
+            .filter(|c| board.tile_at(c).is_some() && board.piece_at(c).is_none())
+            .collect();
+        for piece in pieces.iter().take(6) {
+            let to = free[rng.below(free.len())];
+            board.get_as_mut(piece).unwrap().set_coord(&to);
+            // read while the moved object is still the stale one
+            assert_lookups_match_scan(&board);
+        }
+        assert_lookups_match_scan(&board);
+    }
+}
+
+#[test]
+fn lookups_follow_added_and_removed_objects() {
+    for map in MAPS {
+        let mut board = board(map);
+        let piece = board.pieces()[0].clone();
+        // a stale object, then an added one on the same coord
+        board.get_as_mut(&piece).unwrap().props.dead = true;
+        let oid = board.next_oid();
+        board.add_object(Object::new(oid, ObjectType::Dasher, piece.coord, Player::A));
+        assert_lookups_match_scan(&board);
+        assert_eq!(board.contents(&piece.coord).unwrap().props.oid, oid);
+
+        board.remove_object(&piece);
+        assert_lookups_match_scan(&board);
+        assert!(board.get(piece.props.oid).is_none());
+        assert_eq!(board.piece_at(&piece.coord).unwrap().props.oid, oid);
+        assert!(hash_as_if_new(&board));
+    }
+}
+
+#[test]
+fn lookups_follow_objects_changed_all_at_once() {
+    for map in MAPS {
+        let mut board = board(map);
+        // every piece moves on to where the next one was
+        let mut pieces = board.pieces_mut();
+        let first = pieces[0].coord;
+        for i in 0..pieces.len() {
+            let to = pieces.get(i + 1).map_or(first, |p| p.coord);
+            pieces[i].set_coord(&to);
+        }
+        assert_lookups_match_scan(&board);
+        assert!(hash_as_if_new(&board));
+
+        for (i, tile) in board.tiles_mut().into_iter().enumerate() {
+            tile.props.dead = i % 3 == 0;
+        }
+        assert_lookups_match_scan(&board);
+        assert!(hash_as_if_new(&board));
+
+        // handed out again, along with some still stale from before
+        let piece = board.pieces()[0].clone();
+        let last = board.objects_mut().pop().unwrap();
+        last.props.dead = !last.props.dead;
+        board.get_as_mut(&piece).unwrap().props.dead = true;
+        assert_lookups_match_scan(&board);
+        assert!(hash_as_if_new(&board));
+        let free = board
+            .tiles()
+            .into_iter()
+            .map(|t| t.coord)
+            .find(|c| board.piece_at(c).is_none())
+            .unwrap();
+        let oid = board.next_oid();
+        board.add_object(Object::new(oid, ObjectType::Jumper, free, Player::B));
+        assert_lookups_match_scan(&board);
+        assert!(hash_as_if_new(&board));
+    }
+}
+
+#[test]
+fn hashes_after_changes_in_place_are_as_if_new() {
+    let rules = RuleSet::default();
+    let mut compared = 0;
+    for map in MAPS {
+        let mut rng = Rng::new(5);
+        let mut position = Position::new(&board(map), Player::A, 0, &rules);
+        for _ in 0..30 {
+            let moves = position.legal_moves();
+            if moves.is_empty() || position.winner().is_some() {
+                break;
+            }
+            position.advance(Some(&moves[rng.below(moves.len())]));
+            assert_lookups_match_scan(&position.board);
+            compared += hash_as_if_new(&position.board) as usize;
+        }
+        let piece = position.board.pieces()[0].clone();
+        let to = position
+            .board
+            .tiles()
+            .into_iter()
+            .map(|t| t.coord)
+            .find(|c| position.board.piece_at(c).is_none());
+        if let Some(to) = to {
+            position.board.get_as_mut(&piece).unwrap().set_coord(&to);
+            position.board.get_as_mut(&piece).unwrap();
+            compared += hash_as_if_new(&position.board) as usize;
+        }
+    }
+    assert!(compared > 0);
+}
@@ -0,0 +1,142 @@
+use hexaroni::ai::{Bot, Evaluation, Mcts, Minimax, Position, RandomBot};
+use hexaroni::config::CONF;
+use hexaroni::engine::{Board, Player, RuleSet};
+use hexaroni::game::{moves::Move, notation::GameRecord, GameController, ManualClock};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// plays its first legal move, once it is let go
+struct GatedBot {
+    gate: Receiver<()>,
+}
+
+impl Bot for GatedBot {
+    fn choose_move(&mut self, position: &Position) -> Option<Move> {
+        self.gate.recv().unwrap();
+        position.legal_moves().into_iter().next()
+    }
+}
+
+/// a game on `Board::test_square` where it is A's turn, and A is `bot`
+fn game_with(bot: Box<dyn Bot>, clock: &mut ManualClock) -> GameController {
+    let mut game = GameController::with_board(Board::test_square());
+    game.set_computer(Player::A, bot);
+    game.bots_in_background = true;
+    game.start_game(clock);
+    clock.advance(CONF.get().game_start_countdown + 0.1);
+    game.tick(clock);
+    clock.advance(CONF.get().ai_move_delay + 0.1);
+    game
+}
+
+fn tick_until(
+    game: &mut GameController,
+    clock: &ManualClock,
+    done: impl Fn(&GameController) -> bool,
+) {
+    let start = Instant::now();
+    while !done(game) {
+        assert!(start.elapsed() < Duration::from_secs(60), "bot never moved");
+        thread::sleep(Duration::from_millis(5));
+        game.tick(clock);
+    }
+}
+
+#[test]
+fn bots_think_in_the_background() {
+    let (open, gate) = channel();
+    let mut clock = ManualClock::new(0.0);
+    let mut game = game_with(Box::new(GatedBot { gate }), &mut clock);
+    // the search starts, and the game goes on without its move
+    game.tick(&clock);
+    game.tick(&clock);
+    assert!(game.history.is_empty());
+    assert!(game.is_computer(Player::A));
+
+    open.send(()).unwrap();
+    tick_until(&mut game, &clock, |g| !g.history.is_empty());
+    assert_eq!(game.history.played().count(), 1);
+    assert_eq!(game.current_player(), Player::B);
+    assert!(game.bots.contains_key(&Player::A));
+}
+
+#[test]
+fn moves_found_too_late_are_dropped() {
+    let (open, gate) = channel();
+    let mut clock = ManualClock::new(0.0);
+    let mut game = game_with(Box::new(GatedBot { gate }), &mut clock);
+    game.tick(&clock);
+    // A's flag falls while it is thinking
+    clock.advance(game.rules.play_move_timeout + 1.0);
+    game.tick(&clock);
+    let turns = game.history.len();
+    assert_eq!(turns, 1);
+
+    open.send(()).unwrap();
+    tick_until(&mut game, &clock, |g| g.bots.contains_key(&Player::A));
+    assert_eq!(game.history.len(), turns);
+}
+
+#[test]
+fn headless_bots_move_right_away() {
+    let mut clock = ManualClock::new(0.0);
+    let bot = Minimax::new(1, Evaluation::default());
+    let mut game = game_with(Box::new(bot), &mut clock);
+    game.bots_in_background = false;
+    game.tick(&clock);
+    assert_eq!(game.history.played().count(), 1);
+}
+
+#[test]
+fn mcts_on_a_playout_budget_picks_the_same_on_any_number_of_threads() {
+    let rules = RuleSet::default();
+    let position = Position::new(&Board::test_square(), Player::A, 0, &rules);
+    let choice = |seed, threads| {
+        let mut mcts = Mcts::new(seed);
+        mcts.iterations = Some(10);
+        mcts.max_playout = 20;
+        mcts.threads = threads;
+        mcts.choose_move(&position)
+            .map(|m| (m.object.props.oid, m.path))
+    };
+    for seed in 0..2 {
+        let first = choice(seed, 1);
+        assert_eq!(choice(seed, 1), first);
+        assert_eq!(choice(seed, 3), first);
+        assert_eq!(choice(seed, 8), first);
+    }
+}
+
+/// a game between two bots, played out headless
+fn bot_game(seed: u64, threads: usize) -> GameRecord {
+    let mut clock = ManualClock::new(0.0);
+    let mut game = GameController::with_board(Board::test_square());
+    game.set_seed(seed);
+    let mut mcts = Mcts::new(0);
+    mcts.iterations = Some(5);
+    mcts.max_playout = 20;
+    mcts.threads = threads;
+    game.set_computer(Player::A, Box::new(mcts));
+    game.set_computer(Player::B, Box::new(RandomBot::new(0)));
+    game.start_game(&clock);
+    clock.advance(CONF.get().game_start_countdown + 0.1);
+    game.tick(&clock);
+    for _ in 0..30 {
+        if game.game_state.outcome().is_some() {
+            break;
+        }
+        clock.advance(CONF.get().ai_move_delay + 0.1);
+        game.tick(&clock);
+    }
+    GameRecord::from_controller(&game)
+}
+
+#[test]
+fn games_between_bots_follow_from_the_seed() {
+    let record = bot_game(0, 1);
+    assert!(record.turns.len() > 2);
+    assert_eq!(bot_game(0, 1), record);
+    assert_eq!(bot_game(0, 4), record);
+    assert_ne!(bot_game(1, 1).turns, record.turns);
+}
@@ -0,0 +1,10 @@
+use hexaroni::engine::Board;
+use std::path::Path;
+
+/// the maps that come with the game
+pub const MAPS: [&str; 5] = ["test_square", "bombers", "pushers", "elements", "hexagon"];
+
+/// one of `MAPS`, as the game loads it
+pub fn board(map: &str) -> Board {
+    Board::load(Path::new(&format!("maps/{}.map", map))).unwrap()
+}
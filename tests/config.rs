@@ -0,0 +1,184 @@
+use hexaroni::config::{self, Config, ConfigError, ConfigSources, CONF};
+use hexaroni::engine::{statuses::StatusType, Player, TimeControl};
+use std::path::PathBuf;
+use std::{env, fs};
+
+/// a config file with `text` in it, unique to this test run
+fn config_file(name: &str, text: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("hexaroni-{}-{}.conf", std::process::id(), name));
+    fs::write(&path, text).unwrap();
+    path
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn every_value_reads_back_as_written() {
+    let mut changed = Config::default();
+    changed.set("play_move_timeout", "7.25").unwrap();
+    changed.set("player_color.B", "0.1 0.2 0.3 1").unwrap();
+    changed.set("time_control", "byo-yomi:60+3x10").unwrap();
+    for config in [Config::default(), changed] {
+        let path = config_file("round-trip", &config.to_string());
+        let mut read = Config::default();
+        read.apply_file(&path).unwrap();
+        assert_eq!(read, config);
+        for (key, value) in config.entries() {
+            assert_eq!(Config::default().set(&key, &value), Ok(()), "{}", key);
+        }
+    }
+}
+
+#[test]
+fn files_are_parsed() {
+    let path = config_file(
+        "parse",
+        "# comments start with '#'\n\
+         play_move_timeout = 3.5  # and may end a line\n\
+         \n\
+         dasher_can_fly=true\n\
+         starting_player = B\n\
+         camera_position = 0.5 1.5 -10.0\n\
+         falling_tiles_indicator = wobble 0.2 37.1\n\
+         time_control = fischer:300+2\n",
+    );
+    let mut config = Config::default();
+    config.apply_file(&path).unwrap();
+    assert_eq!(config.play_move_timeout, 3.5);
+    assert!(config.dasher_can_fly);
+    assert_eq!(config.starting_player, Player::B);
+    assert_eq!(config.camera_position.to_array(), [0.5, 1.5, -10.0]);
+    assert_eq!(
+        config.falling_tiles_indicator,
+        StatusType::Wobble {
+            amplitude: 0.2,
+            speed: 37.1
+        }
+    );
+    assert_eq!(
+        config.time_control,
+        TimeControl::Fischer {
+            main: 300.0,
+            increment: 2.0
+        }
+    );
+}
+
+#[test]
+fn bad_lines_say_where_they_are() {
+    for (name, text) in [
+        ("no-equals", "play_move_timeout 3.5"),
+        ("unknown-key", "\nno_such_key = 1"),
+        ("bad-value", "play_move_timeout = soon"),
+        ("bad-color", "tile_base_color = 1 0 0"),
+    ] {
+        let path = config_file(name, text);
+        match Config::default().apply_file(&path) {
+            Err(ConfigError::Parse { origin, .. }) => {
+                let line = text.lines().count();
+                assert_eq!(
+                    origin,
+                    format!("{} line {}", path.display(), line),
+                    "{}",
+                    name
+                );
+            }
+            other => panic!("{}: expected a parse error, got {:?}", name, other),
+        }
+    }
+    let missing = Config::default().apply_file(&env::temp_dir().join("hexaroni-no-such.conf"));
+    assert!(matches!(missing, Err(ConfigError::Io(_))));
+}
+
+#[test]
+fn values_that_make_no_sense_are_refused() {
+    assert_eq!(Config::default().validate(), Ok(()));
+    for (key, value) in [
+        ("mcts_threads", "0"),
+        ("ai_depth", "0"),
+        ("mcts_think_fraction", "1.5"),
+        ("ai_move_delay", "60"),
+    ] {
+        let mut config = Config::default();
+        config.set(key, value).unwrap();
+        assert!(
+            matches!(config.validate(), Err(ConfigError::Invalid { key: k, .. }) if k == key),
+            "{} = {}",
+            key,
+            value
+        );
+        let sources = ConfigSources {
+            file: None,
+            overrides: vec![(key.to_string(), value.to_string())],
+        };
+        assert!(Config::from_sources(&sources).is_err());
+    }
+}
+
+#[test]
+fn later_sources_win() {
+    // only this test touches `kill_duration` through the environment
+    let path = config_file("order", "kill_duration = 1\nrender_scale = 2\n");
+    let sources = |set: &[&str]| {
+        let mut line = args(&["hexaroni", "--config", path.to_str().unwrap()]);
+        for s in set {
+            line.extend(args(&["--set", s]));
+        }
+        ConfigSources::from_args(&line).unwrap()
+    };
+    let kill_duration = |sources| Config::from_sources(&sources).unwrap().kill_duration;
+
+    assert_eq!(Config::default().kill_duration, 0.4);
+    assert_eq!(kill_duration(sources(&[])), 1.0);
+    env::set_var("HEXARONI_KILL_DURATION", "2");
+    assert_eq!(kill_duration(sources(&[])), 2.0);
+    assert_eq!(kill_duration(sources(&["kill_duration=3"])), 3.0);
+    // the last --set of a key counts
+    assert_eq!(
+        kill_duration(sources(&["kill_duration=3", "kill_duration = 4"])),
+        4.0
+    );
+    env::remove_var("HEXARONI_KILL_DURATION");
+    // values from the file that nothing else sets are kept
+    let config = Config::from_sources(&sources(&["kill_duration=3"])).unwrap();
+    assert_eq!((config.kill_duration, config.render_scale), (3.0, 2.0));
+}
+
+#[test]
+fn command_lines_are_read() {
+    let sources = ConfigSources::from_args(&args(&[
+        "hexaroni",
+        "--map",
+        "maps/hexagon.map",
+        "--config",
+        "my.conf",
+        "--set",
+        "ai_depth=4",
+    ]))
+    .unwrap();
+    assert_eq!(sources.file, Some(PathBuf::from("my.conf")));
+    assert_eq!(
+        sources.overrides,
+        vec![("ai_depth".to_string(), "4".to_string())]
+    );
+    assert!(ConfigSources::from_args(&args(&["hexaroni", "--set", "ai_depth"])).is_err());
+    assert!(ConfigSources::from_args(&args(&["hexaroni", "--config"])).is_err());
+}
+
+#[test]
+fn the_config_in_use_is_swapped_whole() {
+    let before = CONF.get();
+    let mut config = Config::default();
+    config.set("ai_depth", "0").unwrap();
+    assert!(config::set(config).is_err());
+    assert_eq!(CONF.get().ai_depth, before.ai_depth);
+
+    let mut config = Config::default();
+    config.set("ai_depth", "6").unwrap();
+    config::set(config).unwrap();
+    assert_eq!(CONF.get().ai_depth, 6);
+    // whoever held on to the old one still has it
+    assert_eq!(before.ai_depth, Config::default().ai_depth);
+}
@@ -0,0 +1,153 @@
+use hexaroni::ai::Position;
+use hexaroni::config::CONF;
+use hexaroni::engine::{Board, Player, RuleSet, TimeControl};
+use hexaroni::game::{GameController, GameOutcome, GameState, ManualClock, Reason};
+
+/// rules without falling tiles, so that passing back and forth repeats the position
+fn rules(repetitions: usize, no_progress_limit: usize) -> RuleSet {
+    RuleSet {
+        time_control: TimeControl::PerMove,
+        flag_fall_loses: false,
+        repetitions,
+        no_progress_limit,
+        ..RuleSet::no_falling_tiles()
+    }
+}
+
+fn game(board: Board, rules: RuleSet, clock: &mut ManualClock) -> GameController {
+    let mut game = GameController::with_board(board);
+    game.set_rules(rules);
+    game.start_game(clock);
+    clock.advance(CONF.get().game_start_countdown + 0.1);
+    game.tick(clock);
+    game
+}
+
+/// lets the player to move run out of time, which passes their turn
+fn pass(game: &mut GameController, clock: &mut ManualClock) {
+    let turns = game.history.len();
+    clock.advance(game.rules.play_move_timeout + 0.1);
+    game.tick(clock);
+    assert_eq!(game.history.len(), turns + 1);
+}
+
+#[test]
+fn positions_that_come_up_again_are_drawn() {
+    let mut clock = ManualClock::new(0.0);
+    let mut game = game(Board::test_square(), rules(3, 0), &mut clock);
+    let start = game.hash();
+    // A and B passing gets back to where it started, every other turn
+    for turn in 1..4 {
+        pass(&mut game, &mut clock);
+        assert_eq!(game.hash() == start, turn % 2 == 0);
+        assert_eq!(game.game_state.outcome(), None);
+    }
+    pass(&mut game, &mut clock);
+    assert_eq!(game.hash(), start);
+    assert_eq!(
+        game.game_state.outcome(),
+        Some(GameOutcome::draw(Reason::Repetition))
+    );
+}
+
+#[test]
+fn games_where_no_piece_dies_for_too_long_are_drawn() {
+    let board =
+        Board::from_map("size 3\ntiles\n9 9 9\n9 9 9\n9 9 9\npieces\nD . d\n. . .\n. . j").unwrap();
+    let mut clock = ManualClock::new(0.0);
+    let mut game = game(board, rules(0, 3), &mut clock);
+    pass(&mut game, &mut clock);
+    pass(&mut game, &mut clock);
+    assert_eq!(game.moves_since_death, 2);
+
+    // A's Dasher runs over B's
+    let position = Position::new(&game.board, Player::A, 2, &game.rules);
+    let capture = position
+        .legal_moves()
+        .into_iter()
+        .find(|m| !m.effects.is_empty())
+        .unwrap();
+    game.apply_move(&capture, &clock, 0.0);
+    assert_eq!(game.moves_since_death, 0);
+    pass(&mut game, &mut clock);
+    pass(&mut game, &mut clock);
+    assert_eq!(game.moves_since_death, 2);
+    assert_eq!(game.game_state.outcome(), None);
+
+    // stepping back and forth keeps the count
+    assert!(game.undo(&clock));
+    assert_eq!(game.moves_since_death, 1);
+    assert!(game.redo(&clock));
+    assert_eq!(game.moves_since_death, 2);
+
+    pass(&mut game, &mut clock);
+    assert_eq!(
+        game.game_state.outcome(),
+        Some(GameOutcome::draw(Reason::MoveLimit))
+    );
+}
+
+#[test]
+fn boards_that_are_not_being_played_have_no_outcome() {
+    let mut board = Board::from_map("size 2\ntiles\n9 9\n9 9\npieces\nD .\n. d").unwrap();
+    // B has nothing left, which would end a game
+    let piece = board
+        .pieces()
+        .into_iter()
+        .find(|p| p.player == Player::B)
+        .cloned();
+    board.remove_object(&piece.unwrap());
+    let mut clock = ManualClock::new(0.0);
+    let mut game = GameController::with_board(board);
+    game.tick(&clock);
+    assert_eq!(game.game_state, GameState::Waiting);
+    game.start_editing();
+    game.tick(&clock);
+    assert_eq!(game.game_state, GameState::Editing);
+
+    let mut game = GameController::with_board(game.board.clone());
+    game.start_game(&clock);
+    clock.advance(CONF.get().game_start_countdown + 0.1);
+    game.tick(&clock);
+    assert_eq!(
+        game.game_state.outcome(),
+        Some(GameOutcome::win(Player::A, Reason::Elimination))
+    );
+}
+
+#[test]
+fn outcomes_read_back_as_written() {
+    let outcomes = Reason::all()
+        .into_iter()
+        .flat_map(|reason| match reason.is_draw() {
+            true => vec![GameOutcome::draw(reason)],
+            false => vec![
+                GameOutcome::win(Player::A, reason),
+                GameOutcome::win(Player::B, reason),
+            ],
+        });
+    for outcome in outcomes {
+        let text = outcome.to_string();
+        assert_eq!(GameOutcome::parse(&text), Some(outcome), "{}", text);
+        assert_eq!(Reason::parse(outcome.reason.name()), Some(outcome.reason));
+    }
+    assert_eq!(
+        GameOutcome::parse(" B time\n"),
+        Some(GameOutcome::win(Player::B, Reason::Time))
+    );
+    assert_eq!(
+        GameOutcome::parse("draw repetition").map(|o| o.result()),
+        Some("1/2-1/2")
+    );
+    // draws have no winner, and wins are not draws
+    for text in [
+        "A repetition",
+        "draw elimination",
+        "C time",
+        "A",
+        "A timeout",
+        "",
+    ] {
+        assert_eq!(GameOutcome::parse(text), None, "{}", text);
+    }
+}
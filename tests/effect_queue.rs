@@ -0,0 +1,107 @@
+use hexaroni::engine::{statuses::Effect, Object, ObjectType, Player};
+use hexaroni::game::effect_queue::{EffectQueue, Origin, MAX_EFFECTS};
+use hexaroni::geometry::HexCoord;
+
+/// an effect that is told apart from others by `n`
+fn effect(n: usize) -> Effect {
+    let bomber = Object::new(1, ObjectType::Bomber, HexCoord::new(0, 0, 4), Player::A);
+    Effect::Arm {
+        object: bomber,
+        fuse: n,
+    }
+}
+
+fn number(effect: &Effect) -> usize {
+    match effect {
+        Effect::Arm { fuse, .. } => *fuse,
+        other => panic!("not a numbered effect: {:?}", other),
+    }
+}
+
+#[test]
+fn effects_resolve_breadth_first() {
+    let mut queue = EffectQueue::new();
+    queue.extend([effect(1), effect(2)], Origin::Move);
+    queue.push(effect(3), Origin::Object);
+    // 1 causes 10 and 11, 2 causes 20, 10 causes 100
+    let log = queue.resolve(0.5, |e| match number(e) {
+        1 => vec![effect(10), effect(11)],
+        2 => vec![effect(20)],
+        10 => vec![effect(100)],
+        _ => vec![],
+    });
+
+    let order: Vec<usize> = log.effects().map(number).collect();
+    assert_eq!(order, [1, 2, 3, 10, 11, 20, 100]);
+    let origins: Vec<Origin> = log.entries.iter().map(|e| e.origin).collect();
+    assert_eq!(
+        origins,
+        [
+            Origin::Move,
+            Origin::Move,
+            Origin::Object,
+            Origin::Effect(0),
+            Origin::Effect(0),
+            Origin::Effect(1),
+            Origin::Effect(3),
+        ]
+    );
+    let depths: Vec<usize> = log.entries.iter().map(|e| e.depth).collect();
+    assert_eq!(depths, [0, 0, 0, 1, 1, 1, 2]);
+    assert_eq!(log.depth(), 2);
+    let caused_by_1: Vec<usize> = log.from_origin(Origin::Effect(0)).map(number).collect();
+    assert_eq!(caused_by_1, [10, 11]);
+    assert_eq!(log.time, 0.5);
+    assert!(log.skipped.is_empty() && !log.truncated);
+}
+
+#[test]
+fn effects_that_trigger_themselves_stop() {
+    let mut queue = EffectQueue::new();
+    queue.push(effect(1), Origin::Move);
+    let mut applied = 0;
+    let log = queue.resolve(0.0, |e| {
+        applied += 1;
+        vec![e.clone()]
+    });
+    assert_eq!(applied, 1);
+    assert_eq!(log.len(), 1);
+    assert_eq!(log.skipped, [effect(1)]);
+
+    // 1 -> 2 -> 3 -> 1 goes round once
+    queue.push(effect(1), Origin::Move);
+    let log = queue.resolve(0.0, |e| vec![effect(number(e) % 3 + 1)]);
+    let order: Vec<usize> = log.effects().map(number).collect();
+    assert_eq!(order, [1, 2, 3]);
+    assert_eq!(log.skipped, [effect(1)]);
+}
+
+#[test]
+fn effects_like_but_not_equal_to_resolved_ones_still_resolve() {
+    let mut queue = EffectQueue::new();
+    // the same kind of effect on the same object, but not the same effect
+    queue.extend([effect(1), effect(2), effect(1)], Origin::Move);
+    let log = queue.resolve(0.0, |_| vec![]);
+    let order: Vec<usize> = log.effects().map(number).collect();
+    assert_eq!(order, [1, 2]);
+    assert_eq!(log.skipped, [effect(1)]);
+}
+
+#[test]
+fn endless_cascades_are_cut_off() {
+    let mut queue = EffectQueue::new();
+    queue.push(effect(0), Origin::Move);
+    // every effect causes two new ones
+    let log = queue.resolve(0.0, |e| {
+        let n = number(e);
+        vec![effect(2 * n + 1), effect(2 * n + 2)]
+    });
+    assert_eq!(log.len(), MAX_EFFECTS);
+    assert!(log.truncated);
+    let order: Vec<usize> = log.effects().map(number).collect();
+    assert_eq!(order, (0..MAX_EFFECTS).collect::<Vec<_>>());
+
+    // what was left over is gone
+    let log = queue.resolve(0.0, |_| vec![]);
+    assert!(log.is_empty() && !log.truncated);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::{board, MAPS};
+use hexaroni::ai::env::{Env, EnvError, Hook, DIRECTIONS, HOOKS};
+use hexaroni::ai::RandomBot;
+use hexaroni::engine::{Board, RuleSet};
+use hexaroni::geometry::HexCoord;
+use hexaroni::rng::Rng;
+use std::collections::HashSet;
+
+/// checks the actions of the position `env` is in
+fn assert_actions_match_moves(env: &Env) {
+    let moves = env.position().legal_moves();
+    let actions = env.legal_actions();
+    // every legal move has an action, and no two share one
+    assert_eq!(actions.len(), moves.len());
+    let numbers: HashSet<usize> = actions.iter().map(|(a, _)| *a).collect();
+    assert_eq!(numbers.len(), actions.len());
+
+    let mask = env.action_mask();
+    assert_eq!(mask.len(), env.num_actions());
+    for (action, legal) in mask.iter().enumerate() {
+        let expected = numbers.contains(&action) || action == env.pass_action();
+        assert_eq!(*legal, expected, "action {}", action);
+    }
+
+    for (action, r#move) in &actions {
+        assert_eq!(env.encode(r#move), Some(*action));
+        let (slot, direction, hook) = env.decode(*action).unwrap();
+        assert!(direction < DIRECTIONS);
+        assert_eq!(
+            (slot * DIRECTIONS + direction) * HOOKS + hook as usize,
+            *action
+        );
+        if hook == Hook::Straight {
+            assert_eq!(
+                r#move.path[0].get_neighbor(direction, 1),
+                Some(r#move.path[1])
+            );
+        }
+    }
+    assert_eq!(env.decode(env.pass_action()), None);
+}
+
+#[test]
+fn actions_round_trip_and_the_mask_matches_the_legal_moves() {
+    for map in MAPS {
+        let mut env = Env::new(board(map));
+        let mut rng = Rng::new(2);
+        env.reset(0);
+        for _ in 0..40 {
+            assert_actions_match_moves(&env);
+            let actions = env.legal_actions();
+            let action = match actions.is_empty() {
+                true => env.pass_action(),
+                false => actions[rng.below(actions.len())].0,
+            };
+            let (_, _, done, info) = env.step(action).unwrap();
+            assert!(!info.illegal);
+            if done {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn stepping_after_the_end_fails_until_reset() {
+    for map in MAPS {
+        let mut env = Env::with_opponent(board(map), Box::new(RandomBot::new(0)));
+        env.max_moves = 6;
+        env.reset(1);
+        let mut steps = 0;
+        while !env.step(env.pass_action()).unwrap().2 {
+            steps += 1;
+            assert!(steps < env.max_moves);
+        }
+        assert_eq!(env.step(env.pass_action()), Err(EnvError::EpisodeOver));
+        let start = env.reset(1);
+        let (observation, ..) = env.step(env.pass_action()).unwrap();
+        assert_ne!(observation, start);
+    }
+}
+
+#[test]
+fn observations_follow_the_rules_of_the_episode() {
+    let board =
+        Board::from_map("size 3\ntiles\n9 9 9\n9 3 9\n9 9 9\npieces\nD . .\n. . .\n. . d").unwrap();
+    let middle = HexCoord::new(1, 1, 3);
+    let mut env = Env::new(board);
+    env.rules = RuleSet {
+        falling_tiles_heads_up: 1,
+        ..RuleSet::default()
+    };
+    let start = env.reset(0);
+    assert_eq!(start.get(2, &middle), 0.0);
+    // only the next episode is played by new rules
+    env.rules.falling_tiles_heads_up = 5;
+    assert_eq!(env.observe(), start);
+    assert_eq!(env.reset(0).get(2, &middle), 1.0);
+}
+
+#[test]
+fn tiles_that_fell_are_not_observed() {
+    let board = Board::from_map("size 3\ntiles\n9 9 9\n9 2r 9\n9 9 9\npieces\nD . .\n. . .\n. . d")
+        .unwrap();
+    let middle = HexCoord::new(1, 1, 3);
+    let mut env = Env::new(board);
+    let start = env.reset(0);
+    assert_eq!(start.get(0, &middle), 1.0);
+    for _ in 0..3 {
+        env.step(env.pass_action()).unwrap();
+    }
+    // a Regrowing tile is still on the board while it is gone
+    assert!(env.position().board.tile_at(&middle).unwrap().props.dead);
+    assert_eq!(env.observe().get(0, &middle), 0.0);
+}
@@ -0,0 +1,101 @@
+use hexaroni::ai::Position;
+use hexaroni::engine::{Board, ObjectType, Player, RuleSet};
+use hexaroni::game::generator::Generator;
+use hexaroni::geometry::{CubeCoord, HexCoord};
+
+fn generators() -> Vec<Generator> {
+    vec![
+        Generator::default(),
+        Generator {
+            radius: 6,
+            holes: 4,
+            walls: 3,
+            pushers: 2,
+            bombers: 2,
+            jitter: 0,
+            ..Default::default()
+        },
+        Generator {
+            radius: 3,
+            holes: 0,
+            walls: 0,
+            dashers: 1,
+            jumpers: 1,
+            ..Default::default()
+        },
+    ]
+}
+
+fn mirror(board: &Board, coord: &HexCoord) -> HexCoord {
+    let middle = (board.size / 2) as i32;
+    coord
+        .cube()
+        .reflect_point(&CubeCoord::new(middle, middle))
+        .to_hexcoord(board.size)
+        .unwrap()
+}
+
+#[test]
+fn the_same_seed_makes_the_same_board() {
+    let rules = RuleSet::default();
+    for generator in generators() {
+        for seed in 0..5 {
+            let board = generator.generate(seed, &rules).unwrap();
+            let again = generator.generate(seed, &rules).unwrap();
+            assert_eq!(again.to_map(), board.to_map());
+            assert_eq!(
+                again.hash(0, Player::A, &rules),
+                board.hash(0, Player::A, &rules)
+            );
+        }
+        let first = generator.generate(0, &rules).unwrap().to_map();
+        assert!((1..5).any(|seed| generator.generate(seed, &rules).unwrap().to_map() != first));
+    }
+}
+
+#[test]
+fn boards_are_the_same_seen_from_either_side() {
+    let rules = RuleSet::default();
+    for generator in generators() {
+        for seed in 0..5 {
+            let board = generator.generate(seed, &rules).unwrap();
+            for tile in board.tiles() {
+                let other = board.tile_at(&mirror(&board, &tile.coord));
+                assert_eq!(
+                    other.map(|t| t.lifespan()),
+                    Some(tile.lifespan()),
+                    "seed {}",
+                    seed
+                );
+            }
+            for piece in board.pieces() {
+                let other = board.piece_at(&mirror(&board, &piece.coord)).unwrap();
+                assert_eq!(other.otype, piece.otype, "seed {}", seed);
+                let player = match piece.otype {
+                    ObjectType::Wall => piece.player,
+                    _ => piece.player.opponent(),
+                };
+                assert_eq!(other.player, player, "seed {}", seed);
+            }
+        }
+    }
+}
+
+#[test]
+fn both_sides_can_move() {
+    for rules in RuleSet::presets() {
+        for generator in generators() {
+            for seed in 0..20 {
+                let board = generator.generate(seed, &rules).unwrap();
+                let position = Position::new(&board, Player::A, 0, &rules);
+                for player in [Player::A, Player::B] {
+                    assert!(
+                        !position.legal_moves_for(&player).is_empty(),
+                        "seed {}",
+                        seed
+                    );
+                }
+            }
+        }
+    }
+}
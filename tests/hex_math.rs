@@ -0,0 +1,135 @@
+use hexaroni::geometry::{CubeCoord, HexCoord, Shape};
+use proptest::prelude::*;
+use std::collections::HashSet;
+
+fn cube() -> impl Strategy<Value = CubeCoord> {
+    (-50..50, -50..50).prop_map(|(q, r)| CubeCoord::new(q, r))
+}
+
+proptest! {
+    #[test]
+    fn cube_coords_add_up_to_zero(c in cube()) {
+        prop_assert_eq!(c.q + c.r + c.s, 0);
+        prop_assert_eq!(CubeCoord::new(c.axial().0, c.axial().1), c);
+    }
+
+    #[test]
+    fn hexcoords_round_trip(x in 0usize..26, y in 0usize..26) {
+        let coord = HexCoord::new(x, y, 26);
+        prop_assert_eq!(coord.cube().to_hexcoord(26), Some(coord));
+    }
+
+    #[test]
+    fn neighbours_agree_with_hexcoord(x in 0usize..9, y in 0usize..9, direction in 0usize..6) {
+        let coord = HexCoord::new(x, y, 9);
+        let expected = coord.get_neighbor(direction, 1);
+        prop_assert_eq!(coord.cube().neighbor(direction).to_hexcoord(9), expected);
+    }
+
+    #[test]
+    fn distance_is_a_metric(a in cube(), b in cube(), c in cube()) {
+        prop_assert_eq!(a.distance(&b), b.distance(&a));
+        prop_assert_eq!(a.distance(&a), 0);
+        prop_assert!(a.distance(&c) <= a.distance(&b) + b.distance(&c));
+        for n in a.neighbours() {
+            prop_assert_eq!(a.distance(&n), 1);
+        }
+    }
+
+    #[test]
+    fn lines_are_connected(a in cube(), b in cube()) {
+        let line = a.line_to(&b);
+        prop_assert_eq!(line.len() as i32, a.distance(&b) + 1);
+        prop_assert_eq!(line[0], a);
+        prop_assert_eq!(*line.last().unwrap(), b);
+        for pair in line.windows(2) {
+            prop_assert_eq!(pair[0].distance(&pair[1]), 1);
+        }
+    }
+
+    #[test]
+    fn lines_along_a_direction_are_straight(a in cube(), direction in 0usize..6, n in 0i32..20) {
+        let b = a + CubeCoord::DIRECTIONS[direction] * n;
+        let expected: Vec<CubeCoord> =
+            (0..=n).map(|i| a + CubeCoord::DIRECTIONS[direction] * i).collect();
+        prop_assert_eq!(a.line_to(&b), expected);
+    }
+
+    #[test]
+    fn rings_and_spirals(c in cube(), radius in 0usize..12) {
+        let ring = c.ring(radius);
+        prop_assert_eq!(ring.len(), if radius == 0 { 1 } else { 6 * radius });
+        prop_assert!(ring.iter().all(|x| x.distance(&c) == radius as i32));
+        prop_assert_eq!(ring.iter().collect::<HashSet<_>>().len(), ring.len());
+
+        let spiral = c.spiral(radius);
+        prop_assert_eq!(spiral.len(), 1 + 3 * radius * (radius + 1));
+        let spiral: HashSet<CubeCoord> = spiral.into_iter().collect();
+        let range: HashSet<CubeCoord> = c.range(radius).into_iter().collect();
+        prop_assert_eq!(spiral, range);
+    }
+
+    #[test]
+    fn rotations(a in cube(), centre in cube(), steps in -12i32..12) {
+        prop_assert_eq!(a.rotate(&centre, 6), a);
+        prop_assert_eq!(a.rotate(&centre, steps).rotate(&centre, -steps), a);
+        prop_assert_eq!(a.rotate(&centre, steps).distance(&centre), a.distance(&centre));
+        prop_assert_eq!(a.rotate(&centre, 3), a.reflect_point(&centre));
+    }
+
+    #[test]
+    fn rotating_turns_directions(direction in 0usize..6) {
+        let origin = CubeCoord::default();
+        let turned = CubeCoord::DIRECTIONS[direction].rotate(&origin, 1);
+        prop_assert_eq!(turned, CubeCoord::DIRECTIONS[(direction + 1) % 6]);
+    }
+
+    #[test]
+    fn reflections(a in cube(), b in cube(), centre in cube()) {
+        for reflect in [CubeCoord::reflect_q, CubeCoord::reflect_r, CubeCoord::reflect_s] {
+            prop_assert_eq!(reflect(&reflect(&a, &centre), &centre), a);
+            prop_assert_eq!(reflect(&a, &centre).distance(&reflect(&b, &centre)), a.distance(&b));
+        }
+        prop_assert_eq!(a.reflect_q(&centre).q, a.q);
+        prop_assert_eq!(a.reflect_r(&centre).r, a.r);
+        prop_assert_eq!(a.reflect_s(&centre).s, a.s);
+    }
+
+    #[test]
+    fn field_of_view(origin in cube(), radius in 0usize..6, walls in prop::collection::vec(cube(), 0..20)) {
+        let open = origin.field_of_view(radius, |_| false);
+        prop_assert_eq!(open.len(), origin.range(radius).len());
+        let walls: HashSet<CubeCoord> = walls.into_iter().collect();
+        let seen = origin.field_of_view(radius, |c| walls.contains(c));
+        for c in &seen {
+            prop_assert!(c.distance(&origin) <= radius as i32);
+            let line = origin.line_to(c);
+            let between = &line[1..line.len().max(2) - 1];
+            prop_assert!(between.iter().all(|x| !walls.contains(x)));
+        }
+        // nothing stands between neighbours
+        if radius > 0 {
+            for n in origin.neighbours() {
+                prop_assert!(seen.contains(&n));
+            }
+        }
+    }
+
+    #[test]
+    fn range_queries_on_a_shape(radius in 0usize..6, x in 0usize..7, y in 0usize..7) {
+        let shape = Shape::hexagon(3);
+        let coord = HexCoord::new(x, y, 7);
+        let within = shape.within(&coord, radius);
+        prop_assert!(within.iter().all(|c| shape.contains(c) && c.distance(&coord) <= radius));
+        let expected = shape.cells().into_iter().filter(|c| c.distance(&coord) <= radius).count();
+        prop_assert_eq!(within.len(), expected);
+    }
+}
+
+#[test]
+fn hexagons() {
+    for radius in 0..6 {
+        let shape = Shape::hexagon(radius);
+        assert_eq!(shape.cells().len(), 1 + 3 * radius * (radius + 1));
+    }
+}
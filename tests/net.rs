@@ -0,0 +1,80 @@
+use hexaroni::ai::Position;
+use hexaroni::config::CONF;
+use hexaroni::engine::{Board, Player, RuleSet, TimeControl};
+use hexaroni::game::{moves::Move, Reason};
+use hexaroni::net::{Client, Server, ServerMessage};
+use std::thread;
+use std::time::Duration;
+
+fn next(client: &Client) -> ServerMessage {
+    client
+        .wait()
+        .expect("connection closed")
+        .expect("a message")
+}
+
+fn assert_rejected(client: &Client) {
+    match next(client) {
+        ServerMessage::Rejected(_) => {}
+        other => panic!("expected a rejection, got '{}'", other),
+    }
+}
+
+#[test]
+fn a_game_over_localhost() {
+    let rules = RuleSet {
+        play_move_timeout: 600.0,
+        time_control: TimeControl::PerMove,
+        ..RuleSet::default()
+    };
+    let server = Server::bind("127.0.0.1:0", Board::test_square(), rules).unwrap();
+    let addr = server.local_addr().unwrap();
+    let host = thread::spawn(move || server.play_game());
+
+    let (mut a, board, sent_rules) = Client::connect(addr).unwrap();
+    let (mut b, _, _) = Client::connect(addr).unwrap();
+    assert_eq!((a.player, b.player), (Player::A, Player::B));
+    assert_eq!(sent_rules, rules);
+    assert_eq!(next(&a), ServerMessage::Start);
+    assert_eq!(next(&b), ServerMessage::Start);
+
+    let position = Position::new(&board, Player::A, 0, &rules);
+    let a_move = position.legal_moves()[0].clone();
+    let b_move = position.legal_moves_for(&Player::B)[0].clone();
+    // the server starts the game after its countdown
+    thread::sleep(Duration::from_secs_f32(
+        CONF.get().game_start_countdown + 0.2,
+    ));
+    // out of turn
+    b.send_move(&b_move).unwrap();
+    assert_rejected(&b);
+
+    a.send_move(&a_move).unwrap();
+    for client in [&a, &b] {
+        match next(client) {
+            ServerMessage::Turn {
+                player, notation, ..
+            } => {
+                assert_eq!(player, Player::A);
+                assert!(notation.is_some());
+            }
+            other => panic!("expected A's turn, got '{}'", other),
+        }
+    }
+
+    // a piece that isn't B's, and a step that is no move at all
+    b.send_move(&a_move).unwrap();
+    assert_rejected(&b);
+    let nowhere = Move::new(b_move.object.clone(), vec![b_move.path[0]; 2], vec![]);
+    b.send_move(&nowhere).unwrap();
+    assert_rejected(&b);
+
+    drop(b);
+    let outcome = host.join().unwrap().unwrap();
+    assert_eq!(outcome.winner, Some(Player::A));
+    assert_eq!(outcome.reason, Reason::Disconnect);
+    match next(&a) {
+        ServerMessage::GameOver { outcome: sent } => assert_eq!(sent, outcome),
+        other => panic!("expected the game to be over, got '{}'", other),
+    }
+}
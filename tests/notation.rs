@@ -0,0 +1,220 @@
+mod common;
+
+use common::{board, MAPS};
+use hexaroni::ai::Position;
+use hexaroni::engine::{Board, ObjectType, Player, RuleSet};
+use hexaroni::game::notation::{
+    coord_to_string, parse_coord, GameRecord, MoveNotation, NotationError,
+};
+use hexaroni::game::{moves::Move, GameController, ManualClock};
+use hexaroni::geometry::HexCoord;
+use hexaroni::rng::Rng;
+
+fn random_move(position: &Position, rng: &mut Rng) -> Option<Move> {
+    let moves = position.legal_moves();
+    (!moves.is_empty()).then(|| moves[rng.below(moves.len())].clone())
+}
+
+fn assert_round_trips(r#move: &Move, position: &Position) {
+    let notation = MoveNotation::from_move(r#move);
+    let text = notation.to_string();
+    let parsed = MoveNotation::parse(&text, position.board.size).unwrap();
+    assert_eq!(parsed, notation, "{}", text);
+    let resolved = parsed
+        .resolve(&position.board, &position.to_move, &position.rules)
+        .unwrap();
+    assert_eq!(
+        resolved.object.props.oid, r#move.object.props.oid,
+        "{}",
+        text
+    );
+    assert_eq!(resolved.path, r#move.path, "{}", text);
+}
+
+#[test]
+fn boards_wider_than_the_alphabet_round_trip() {
+    let size = 30;
+    let grid = |entry: fn(usize, usize) -> &'static str| {
+        let row = |y| (0..size).map(|x| entry(x, y)).collect::<Vec<_>>().join(" ");
+        (0..size).map(row).collect::<Vec<_>>().join("\n")
+    };
+    let tiles = grid(|_, _| "9");
+    // A's Dasher can get past z, and B's is out of its way
+    let pieces = grid(|x, y| match (x, y) {
+        (24, 0) => "D",
+        (1, 29) => "d",
+        _ => ".",
+    });
+    let map = format!("size {}\ntiles\n{}\npieces\n{}", size, tiles, pieces);
+    let board = Board::from_map(&map).unwrap();
+    assert_eq!(
+        Board::from_map(&board.to_map()).unwrap().to_map(),
+        board.to_map()
+    );
+
+    let position = Position::new(&board, Player::A, 0, &RuleSet::default());
+    let moves = position.legal_moves();
+    assert!(moves.iter().any(|m| m.target().x >= 26));
+    for r#move in &moves {
+        assert_round_trips(r#move, &position);
+    }
+}
+
+#[test]
+fn every_legal_move_round_trips() {
+    let rules = RuleSet::default();
+    for map in MAPS {
+        let mut rng = Rng::new(11);
+        let mut position = Position::new(&board(map), Player::A, 0, &rules);
+        for _ in 0..20 {
+            if position.winner().is_some() {
+                break;
+            }
+            // both players' moves, not only those of the one to move
+            for player in [Player::A, Player::B] {
+                let position = Position {
+                    to_move: player,
+                    ..position.clone()
+                };
+                for r#move in position.legal_moves() {
+                    assert_round_trips(&r#move, &position);
+                }
+            }
+            position.advance(random_move(&position, &mut rng).as_ref());
+        }
+    }
+}
+
+#[test]
+fn game_records_round_trip() {
+    for map in MAPS {
+        let mut rng = Rng::new(4);
+        let mut clock = ManualClock::new(0.0);
+        let mut game = GameController::with_board(board(map));
+        game.set_seed(17);
+        game.start_game(&clock);
+        clock.advance(10.0);
+        game.tick(&clock);
+        for move_nr in 0..30 {
+            if game.game_state.outcome().is_some() {
+                break;
+            }
+            let position = Position::new(&game.board, game.current_player(), move_nr, &game.rules);
+            clock.advance(0.25);
+            match random_move(&position, &mut rng) {
+                Some(r#move) => game.apply_move(&r#move, &clock, 0.5),
+                None => break,
+            }
+            clock.advance(0.5);
+            game.tick(&clock);
+        }
+        let record = GameRecord::from_controller(&game);
+        let parsed = GameRecord::parse(&record.to_string()).unwrap();
+        assert_eq!(parsed, record);
+        let replayed = parsed.replay().unwrap();
+        assert_eq!(replayed.hash(), game.hash());
+        assert_eq!(GameRecord::from_controller(&replayed).turns, record.turns);
+    }
+}
+
+#[test]
+fn files_go_on_past_z() {
+    let size = 800;
+    for (x, file) in [
+        (0, "a"),
+        (25, "z"),
+        (26, "aa"),
+        (51, "az"),
+        (52, "ba"),
+        (702, "aaa"),
+    ] {
+        let coord = HexCoord::new(x, 3, size);
+        assert_eq!(coord_to_string(&coord), format!("{}4", file));
+        assert_eq!(parse_coord(&format!("{}4", file), size), Ok(coord));
+    }
+    for x in 0..size {
+        let coord = HexCoord::new(x, x, size);
+        assert_eq!(parse_coord(&coord_to_string(&coord), size), Ok(coord));
+    }
+    assert!(matches!(
+        parse_coord("aa1", 26),
+        Err(NotationError::CoordOutOfBounds(_))
+    ));
+    assert!(parse_coord("4", size).is_err());
+    assert!(parse_coord("a", size).is_err());
+}
+
+#[test]
+fn files_with_an_x_are_not_captures() {
+    let size = 700;
+    let coord = |x, y| HexCoord::new(x, y, size);
+    let notation = MoveNotation {
+        piece: ObjectType::Dasher,
+        // `x1-ax1xxa2xx6`
+        path: vec![coord(23, 0), coord(49, 0)],
+        captures: vec![coord(624, 1), coord(23, 5)],
+    };
+    let text = notation.to_string();
+    assert_eq!(text, "Dx1-ax1xxa2xx6");
+    assert_eq!(MoveNotation::parse(&text, size), Ok(notation), "{}", text);
+}
+
+#[test]
+fn waypoints_must_come_in_order() {
+    let rules = RuleSet::default();
+    let position = Position::new(&Board::test_square(), Player::A, 0, &rules);
+    let long = position
+        .legal_moves()
+        .into_iter()
+        .find(|m| m.path.len() >= 4)
+        .expect("a move of three steps or more");
+    let (from, a, b, to) = (long.path[0], long.path[1], long.path[2], *long.target());
+    let written = |path| MoveNotation {
+        piece: long.object.otype,
+        path,
+        captures: MoveNotation::from_move(&long).captures,
+    };
+    let resolve = |n: MoveNotation| n.resolve(&position.board, &Player::A, &rules);
+    assert!(resolve(written(vec![from, a, b, to])).is_ok());
+    assert!(matches!(
+        resolve(written(vec![from, b, a, to])),
+        Err(NotationError::IllegalMove(_))
+    ));
+    assert!(matches!(
+        resolve(written(vec![])),
+        Err(NotationError::Syntax(_))
+    ));
+}
+
+#[test]
+fn replays_stop_when_out_of_sync() {
+    let rules = RuleSet::default();
+    let mut clock = ManualClock::new(0.0);
+    let mut game = GameController::with_board(Board::test_square());
+    game.start_game(&clock);
+    clock.advance(10.0);
+    game.tick(&clock);
+    for move_nr in 0..2 {
+        let position = Position::new(&game.board, game.current_player(), move_nr, &rules);
+        game.apply_move(&position.legal_moves()[0], &clock, 0.5);
+        clock.advance(1.0);
+        game.tick(&clock);
+    }
+    let mut record = GameRecord::from_controller(&game);
+    let mut replay = GameController::new_replay(record.clone(), &clock).unwrap();
+    // A's first move again, from where the piece has left
+    record.turns[1] = record.turns[0].clone();
+    replay.replay.as_mut().unwrap().record = record;
+
+    assert!(replay.redo(&clock));
+    assert!(!replay.redo(&clock));
+    let stopped = replay.replay.as_ref().unwrap();
+    assert!(stopped.error.is_some());
+    assert_eq!(stopped.next_turn, 1);
+    clock.advance(60.0);
+    replay.tick(&clock);
+    assert_eq!(replay.replay.as_ref().unwrap().next_turn, 1);
+
+    assert!(replay.undo(&clock));
+    assert!(replay.replay.as_ref().unwrap().error.is_none());
+}
@@ -0,0 +1,148 @@
+use hexaroni::ai::Position;
+use hexaroni::config::CONF;
+use hexaroni::engine::{Board, Player, RuleSet, TimeControl};
+use hexaroni::game::{notation::GameRecord, Clock, GameController, GameState, ManualClock};
+use hexaroni::geometry::HexCoord;
+use hexaroni::rng::Rng;
+use itertools::Itertools;
+use std::path::Path;
+
+/// a game on `map` played at random for `turns` turns, with think times that don't round
+fn played_game(map: &str, seed: u64, turns: usize, clock: &mut ManualClock) -> GameController {
+    let rules = RuleSet {
+        time_control: TimeControl::Fischer {
+            main: 30.0,
+            increment: 1.5,
+        },
+        flag_fall_loses: false,
+        ..RuleSet::default()
+    };
+    let mut game =
+        GameController::with_board(Board::load(Path::new(&format!("maps/{}.map", map))).unwrap());
+    game.set_rules(rules);
+    game.set_seed(seed);
+    game.start_game(clock);
+    clock.advance(CONF.get().game_start_countdown + 0.1);
+    game.tick(clock);
+
+    let mut rng = Rng::new(seed);
+    for _ in 0..turns {
+        let GameState::Playing {
+            current_player,
+            move_nr,
+            ..
+        } = game.game_state
+        else {
+            break;
+        };
+        // now and then, someone's flag falls
+        clock.advance(rng.below(1000) as f32 / 97.0);
+        game.tick(clock);
+        if game.game_state.move_nr() != Some(move_nr) {
+            continue;
+        }
+        let position = Position::new(&game.board, current_player, move_nr, &game.rules);
+        let moves = position.legal_moves();
+        if moves.is_empty() {
+            break;
+        }
+        game.apply_move(&moves[rng.below(moves.len())], clock, 0.0);
+    }
+    game
+}
+
+#[test]
+fn resumed_games_are_where_they_were_saved() {
+    let mut resumed_some = 0;
+    for (map, seed) in ["elements", "test_square"].iter().cartesian_product(0..6) {
+        let mut clock = ManualClock::new(0.0);
+        let game = played_game(map, seed, 4 + 3 * seed as usize, &mut clock);
+        if !matches!(game.game_state, GameState::Playing { .. }) {
+            continue;
+        }
+        // part of the way into the current move
+        clock.advance(1.2345);
+        let text = game.save(&clock).to_string();
+
+        let mut later = ManualClock::new(1000.0 * seed as f32);
+        let record = GameRecord::parse(&text).unwrap();
+        let resumed = GameController::resume(&record, &later).unwrap();
+        assert_eq!(resumed.hash(), game.hash(), "{} seed {}", map, seed);
+        assert_eq!(resumed.clocks, game.clocks, "{} seed {}", map, seed);
+        assert_eq!(
+            resumed.rng.state(),
+            game.rng.state(),
+            "{} seed {}",
+            map,
+            seed
+        );
+        assert_eq!(resumed.moves_since_death, game.moves_since_death);
+        assert_eq!(resumed.history.len(), game.history.len());
+        for player in [Player::A, Player::B] {
+            let left = game.time_left(player, clock.now()).unwrap();
+            let resumed_left = resumed.time_left(player, later.now()).unwrap();
+            assert!((left - resumed_left).abs() < 1e-3, "{} seed {}", map, seed);
+        }
+        // and they go on the same
+        clock.advance(2.0);
+        later.advance(2.0);
+        assert_eq!(
+            resumed
+                .time_left(resumed.current_player(), later.now())
+                .unwrap()
+                > 0.0,
+            game.time_left(game.current_player(), clock.now()).unwrap() > 0.0
+        );
+        resumed_some += 1;
+    }
+    assert!(resumed_some > 6);
+}
+
+#[test]
+fn flights_over_tiles_that_just_fell_replay() {
+    // the tile between A's Dasher and (2, 0) falls after B's first move
+    let board =
+        Board::from_map("size 3\ntiles\n20 1 20\n20 20 20\n20 20 20\npieces\nD . .\n. . .\nD . d")
+            .unwrap();
+    let at = |x, y| HexCoord::new(x, y, 3);
+    let mut clock = ManualClock::new(0.0);
+    let mut game = GameController::with_board(board);
+    game.set_rules(RuleSet::flying_dashers());
+    game.start_game(&clock);
+    clock.advance(CONF.get().game_start_countdown + 0.1);
+    game.tick(&clock);
+    for from in [at(0, 2), at(2, 2)] {
+        let position = Position::new(&game.board, game.current_player(), 0, &game.rules);
+        let r#move = position
+            .legal_moves()
+            .into_iter()
+            .find(|m| m.object.coord == from);
+        clock.advance(1.0);
+        game.apply_move(&r#move.unwrap(), &clock, 0.0);
+    }
+    // long enough for the fallen tile to be cleared away
+    clock.advance(3.0);
+    game.tick(&clock);
+    assert!(game.board.tile_at(&at(1, 0)).is_none());
+    let move_nr = game.game_state.move_nr().unwrap();
+    let position = Position::new(&game.board, Player::A, move_nr, &game.rules);
+    let flight = position
+        .legal_moves()
+        .into_iter()
+        .find(|m| m.path == [at(0, 0), at(1, 0), at(2, 0)])
+        .expect("a flight over the gap");
+    game.apply_move(&flight, &clock, 0.0);
+
+    // played back with no time in between, the tile is still there, dead
+    let text = game.save(&clock).to_string();
+    let record = GameRecord::parse(&text).unwrap();
+    let replayed = record.replay_from(0.0).unwrap();
+    assert!(replayed.board.tile_at(&at(1, 0)).unwrap().props.dead);
+    let resumed = GameController::resume(&record, &clock).unwrap();
+    for other in [replayed, resumed] {
+        assert_eq!(other.history.len(), game.history.len());
+        assert_eq!(other.hash(), game.hash());
+        let dasher = other.board.piece_at(&at(2, 0)).unwrap();
+        assert_eq!(dasher.props.oid, flight.object.props.oid);
+    }
+}
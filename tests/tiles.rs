@@ -0,0 +1,139 @@
+use hexaroni::ai::Position;
+use hexaroni::engine::{Board, Player, RuleSet, TileType};
+use hexaroni::game::moves::Move;
+use hexaroni::geometry::HexCoord;
+
+fn board(tiles: &str, pieces: &str) -> Board {
+    Board::from_map(&format!("size 3\ntiles\n{}\npieces\n{}", tiles, pieces)).unwrap()
+}
+
+fn at(x: usize, y: usize) -> HexCoord {
+    HexCoord::new(x, y, 3)
+}
+
+fn rules() -> RuleSet {
+    RuleSet {
+        dasher_can_fly: false,
+        bomber_fuse: 3,
+        lava_turns: 3,
+        regrow_turns: 6,
+        ..RuleSet::default()
+    }
+}
+
+/// the legal move of the piece on `from` that ends on `to`, if there is one
+fn move_to(position: &Position, from: HexCoord, to: HexCoord) -> Option<Move> {
+    position
+        .legal_moves()
+        .into_iter()
+        .find(|m| m.object.coord == from && *m.target() == to)
+}
+
+fn pass(position: &mut Position, turns: usize) {
+    for _ in 0..turns {
+        position.advance(None);
+    }
+}
+
+/// A to move on `tiles`, once the tile between (0, 0) and (2, 0) has fallen
+fn with_gap(tiles: &str) -> Position {
+    let board = board(tiles, "D . .\n. . .\n. . d");
+    let mut position = Position::new(&board, Player::A, 0, &rules());
+    pass(&mut position, 2);
+    assert!(position.board.tile_at(&at(1, 0)).is_none());
+    position
+}
+
+#[test]
+fn dashers_slide_off_ice_over_gaps() {
+    let position = with_gap("20i 1 20\n20 20 20\n20 20 20");
+    let slide = move_to(&position, at(0, 0), at(2, 0)).expect("a slide over the gap");
+    assert_eq!(slide.path, [at(0, 0), at(1, 0), at(2, 0)]);
+    let after = position.play(Some(&slide));
+    assert_eq!(
+        after.board.piece_at(&at(2, 0)).unwrap().props.oid,
+        slide.object.props.oid
+    );
+
+    // off ground, the gap stops it
+    let position = with_gap("20 1 20\n20 20 20\n20 20 20");
+    assert!(move_to(&position, at(0, 0), at(2, 0)).is_none());
+}
+
+#[test]
+fn lava_kills_pieces_that_stay_on_it() {
+    let lava = board("20l 20 20\n20 20 20\n20 20 20", "D . .\n. . .\n. . d");
+    let mut position = Position::new(&lava, Player::A, 0, &rules());
+    pass(&mut position, rules().lava_turns);
+    assert!(position.board.piece_at(&at(0, 0)).is_some());
+    pass(&mut position, 1);
+    assert!(position.board.piece_at(&at(0, 0)).is_none());
+    assert!(position.board.piece_at(&at(2, 2)).is_some());
+    assert_eq!(position.winner(), Some(Player::B));
+}
+
+#[test]
+fn teleporters_send_pieces_to_their_partner_if_it_is_free() {
+    let tiles = "20t1 20 20\n20 20 20\n20 20 20t1";
+    let free = board(tiles, ". . D\n. . .\nd . .");
+    let position = Position::new(&free, Player::A, 0, &rules());
+    let r#move = move_to(&position, at(2, 0), at(0, 0)).unwrap();
+    let after = position.play(Some(&r#move));
+    assert!(after.board.piece_at(&at(0, 0)).is_none());
+    assert_eq!(
+        after.board.piece_at(&at(2, 2)).unwrap().props.oid,
+        r#move.object.props.oid
+    );
+
+    // with someone on the partner, the piece stays where it stepped
+    let taken = board(tiles, ". . D\n. . .\n. . d");
+    let position = Position::new(&taken, Player::A, 0, &rules());
+    let r#move = move_to(&position, at(2, 0), at(0, 0)).unwrap();
+    let after = position.play(Some(&r#move));
+    assert_eq!(
+        after.board.piece_at(&at(0, 0)).unwrap().props.oid,
+        r#move.object.props.oid
+    );
+    assert_eq!(after.board.piece_at(&at(2, 2)).unwrap().player, Player::B);
+}
+
+#[test]
+fn regrowing_tiles_come_back_with_a_new_lifespan() {
+    let regrowing = board("40 40 40\n40 2r 40\n40 40 40", "D . .\n. . .\n. . d");
+    let mut position = Position::new(&regrowing, Player::A, 0, &rules());
+    let tile = |position: &Position| position.board.tile_at(&at(1, 1)).cloned().unwrap();
+    assert_eq!(tile(&position).lifespan(), Some(2));
+
+    pass(&mut position, 3);
+    assert!(tile(&position).props.dead);
+    let back = 2 + rules().regrow_turns;
+    pass(&mut position, back - 3);
+    assert!(tile(&position).props.dead);
+    pass(&mut position, 1);
+    let regrown = tile(&position);
+    assert!(!regrown.props.dead);
+    assert_eq!(regrown.tile_type(), TileType::Regrowing);
+    assert_eq!(regrown.lifespan(), Some(back + rules().regrow_turns));
+}
+
+#[test]
+fn bombers_go_off_when_the_fuse_in_the_rules_runs_out() {
+    let bombers = board("40 40 40\n40 40 40\n40 40 40", "B D .\n. . .\n. . d");
+    let position = Position::new(&bombers, Player::A, 0, &rules());
+    let arm = move_to(&position, at(0, 0), at(0, 0)).unwrap();
+    let mut position = position.play(Some(&arm));
+    pass(&mut position, rules().bomber_fuse - 1);
+    assert!(position.board.piece_at(&at(1, 0)).is_some());
+    pass(&mut position, 1);
+    assert!(position.board.piece_at(&at(0, 0)).is_none());
+    assert!(position.board.piece_at(&at(1, 0)).is_none());
+}
+
+#[test]
+fn tile_rules_read_back_as_written() {
+    let rules = rules();
+    assert_eq!(RuleSet::parse(&rules.to_string()), Ok(rules));
+    assert!(RuleSet::parse("standard bomber_fuse=0").is_err());
+    assert!(RuleSet::parse("standard lava_turns=0").is_err());
+    assert!(RuleSet::parse("standard regrow_turns=0").is_err());
+}
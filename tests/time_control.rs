@@ -0,0 +1,207 @@
+use hexaroni::ai::Position;
+use hexaroni::config::CONF;
+use hexaroni::engine::{Board, Player, RuleSet, TimeBank, TimeControl};
+use hexaroni::game::{Clock, GameController, GameOutcome, ManualClock, Reason};
+
+const PER_MOVE: f32 = 10.0;
+
+fn bank(main: f32, periods: usize) -> TimeBank {
+    TimeBank {
+        main,
+        periods,
+        flagged: false,
+    }
+}
+
+fn flagged() -> TimeBank {
+    TimeBank {
+        main: 0.0,
+        periods: 0,
+        flagged: true,
+    }
+}
+
+/// charges a full bank of `control` for each of `moves` in turn
+fn after(control: TimeControl, moves: &[f32]) -> TimeBank {
+    moves.iter().fold(control.start(), |b, &elapsed| {
+        control.charge(b, elapsed, PER_MOVE)
+    })
+}
+
+#[test]
+fn moves_are_charged_by_the_time_control() {
+    let per_move = TimeControl::PerMove;
+    assert_eq!(per_move.time_left(&per_move.start(), 3.0, PER_MOVE), 7.0);
+    assert_eq!(after(per_move, &[30.0]), per_move.start());
+
+    let sudden_death = TimeControl::SuddenDeath { main: 60.0 };
+    assert_eq!(
+        sudden_death.time_left(&sudden_death.start(), 10.0, PER_MOVE),
+        50.0
+    );
+    assert_eq!(after(sudden_death, &[10.0, 20.0]), bank(30.0, 0));
+
+    let fischer = TimeControl::Fischer {
+        main: 60.0,
+        increment: 2.0,
+    };
+    assert_eq!(after(fischer, &[10.0, 1.0]), bank(53.0, 0));
+
+    // only the time past the delay comes off
+    let bronstein = TimeControl::Bronstein {
+        main: 60.0,
+        delay: 3.0,
+    };
+    assert_eq!(after(bronstein, &[2.0]), bank(60.0, 0));
+    assert_eq!(after(bronstein, &[10.0]), bank(53.0, 0));
+
+    let byo_yomi = TimeControl::ByoYomi {
+        main: 10.0,
+        periods: 3,
+        period: 5.0,
+    };
+    assert_eq!(byo_yomi.time_left(&byo_yomi.start(), 0.0, PER_MOVE), 25.0);
+    // into the first period, which is kept
+    assert_eq!(after(byo_yomi, &[12.0]), bank(0.0, 3));
+    // over the first period, which is used up
+    assert_eq!(after(byo_yomi, &[17.0]), bank(0.0, 2));
+    assert_eq!(after(byo_yomi, &[17.0, 4.0]), bank(0.0, 2));
+    assert_eq!(byo_yomi.time_left(&bank(0.0, 2), 4.0, PER_MOVE), 6.0);
+}
+
+#[test]
+fn flags_fall_when_the_bank_runs_out() {
+    let controls = [
+        TimeControl::SuddenDeath { main: 60.0 },
+        TimeControl::Fischer {
+            main: 60.0,
+            increment: 2.0,
+        },
+        TimeControl::Bronstein {
+            main: 60.0,
+            delay: 3.0,
+        },
+        TimeControl::ByoYomi {
+            main: 50.0,
+            periods: 2,
+            period: 5.0,
+        },
+    ];
+    for control in controls {
+        assert!(!after(control, &[59.0]).flagged, "{}", control);
+        assert_eq!(after(control, &[60.5]), flagged(), "{}", control);
+        // an empty bank has run out
+        assert_eq!(after(control, &[60.0]), flagged(), "{}", control);
+        assert_eq!(after(control, &[30.0, 40.0]), flagged(), "{}", control);
+        // once flagged, it is `PER_MOVE` for each move
+        let bank = after(control, &[61.0, 1000.0]);
+        assert_eq!(bank, flagged(), "{}", control);
+        assert_eq!(control.time_left(&bank, 4.0, PER_MOVE), 6.0);
+    }
+}
+
+/// a game where each side has 5 seconds in all, and it is A's turn
+fn game_on_the_clock(flag_fall_loses: bool, clock: &mut ManualClock) -> GameController {
+    let rules = RuleSet {
+        time_control: TimeControl::SuddenDeath { main: 5.0 },
+        flag_fall_loses,
+        ..RuleSet::default()
+    };
+    let mut game = GameController::with_board(Board::test_square());
+    game.set_rules(rules);
+    game.start_game(clock);
+    clock.advance(CONF.get().game_start_countdown + 0.1);
+    game.tick(clock);
+    game
+}
+
+#[test]
+fn running_out_of_time_loses_or_passes() {
+    let mut clock = ManualClock::new(0.0);
+    let mut game = game_on_the_clock(true, &mut clock);
+    clock.advance(4.9);
+    game.tick(&clock);
+    assert_eq!(game.game_state.outcome(), None);
+    clock.advance(0.2);
+    game.tick(&clock);
+    assert_eq!(
+        game.game_state.outcome(),
+        Some(GameOutcome::win(Player::B, Reason::Time))
+    );
+
+    let mut game = game_on_the_clock(false, &mut clock);
+    clock.advance(5.1);
+    game.tick(&clock);
+    assert_eq!(game.game_state.outcome(), None);
+    assert_eq!(game.current_player(), Player::B);
+    assert!(game.clocks[&Player::A].flagged);
+    let per_move = game.rules.play_move_timeout;
+    assert_eq!(game.time_left(Player::A, clock.now()), Some(per_move));
+}
+
+#[test]
+fn moves_made_after_the_flag_fell_are_too_late() {
+    for flag_fall_loses in [true, false] {
+        let mut clock = ManualClock::new(0.0);
+        let mut game = game_on_the_clock(flag_fall_loses, &mut clock);
+        let position = Position::new(&game.board, Player::A, 0, &game.rules);
+        let r#move = position.legal_moves()[0].clone();
+        // no tick in between to notice
+        clock.advance(5.1);
+        game.apply_move(&r#move, &clock, 0.0);
+        assert!(game.history.played().all(|t| t.r#move.is_none()));
+        match flag_fall_loses {
+            true => assert_eq!(
+                game.game_state.outcome(),
+                Some(GameOutcome::win(Player::B, Reason::Time))
+            ),
+            false => {
+                assert_eq!(game.history.len(), 1);
+                assert_eq!(game.current_player(), Player::B);
+                assert!(game.clocks[&Player::A].flagged);
+            }
+        }
+    }
+}
+
+#[test]
+fn time_controls_read_back_as_written() {
+    for text in [
+        "per-move",
+        "sudden-death:300",
+        "fischer:300+2",
+        "bronstein:60.5+3",
+        "byo-yomi:600+5x30",
+        "byo-yomi:0+1x10",
+    ] {
+        let control = TimeControl::parse(text).unwrap();
+        assert_eq!(control.to_string(), text);
+        assert_eq!(TimeControl::parse(&control.to_string()), Ok(control));
+    }
+    for text in [
+        "",
+        "per-move:5",
+        "sudden-death:0",
+        "sudden-death:5+1",
+        "fischer:300",
+        "fischer:-1+2",
+        "bronstein:60+inf",
+        "byo-yomi:600+5",
+        "byo-yomi:600+5x0",
+        "hourglass:60",
+    ] {
+        assert!(TimeControl::parse(text).is_err(), "{}", text);
+    }
+    // banks are saved with games in progress, and must not drift
+    for bank in [
+        bank(283.41074, 0),
+        bank(0.1, 3),
+        bank(1.0 / 3.0, 2),
+        flagged(),
+    ] {
+        assert_eq!(TimeBank::parse(&bank.to_string()), Ok(bank));
+    }
+    for text in ["", "soon", "1+", "+2", "1+-2"] {
+        assert!(TimeBank::parse(text).is_err(), "{}", text);
+    }
+}
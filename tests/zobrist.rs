@@ -0,0 +1,177 @@
+mod common;
+
+use common::{board, MAPS};
+use hexaroni::ai::Position;
+use hexaroni::engine::statuses::{Effect, StatusType};
+use hexaroni::engine::{Board, Player, RuleSet};
+use hexaroni::game::{moves::Move, GameController, ManualClock};
+use hexaroni::rng::Rng;
+use std::collections::HashMap;
+
+/// everything that makes up a position, written out in full
+fn describe(position: &Position) -> Vec<String> {
+    let mut features = vec![format!("{:?} to move", position.to_move)];
+    for o in position.board.objects() {
+        let (x, y) = (o.coord.x, o.coord.y);
+        if o.props.dead {
+            // only what it has lined up counts
+        } else if o.is_tile() {
+            features.push(format!("{:?} tile on {} {}", o.tile_type(), x, y));
+        } else {
+            features.push(format!("{:?} {:?} on {} {}", o.player, o.otype, x, y));
+        }
+        for status in &o.statuses {
+            let now = position.move_nr as i64;
+            match &status.stype {
+                StatusType::DelayedEffect {
+                    move_nr, effect, ..
+                } if position.rules.falling_tiles || !o.is_tile() => {
+                    let due = *move_nr as i64;
+                    let what = match &**effect {
+                        Effect::Regrow { lifespan, .. } => {
+                            format!("regrow for {}", *lifespan as i64 - due)
+                        }
+                        e => format!("{:?}", std::mem::discriminant(e)),
+                    };
+                    features.push(format!("{} on {} {} in {}", what, x, y, due - now));
+                }
+                StatusType::Burning { move_nr } if !o.props.dead => {
+                    let since = *move_nr as i64;
+                    features.push(format!("burning on {} {} for {}", x, y, now - since));
+                }
+                _ => {}
+            }
+        }
+    }
+    features.sort();
+    features
+}
+
+fn random_move(position: &Position, rng: &mut Rng) -> Option<Move> {
+    let moves = position.legal_moves();
+    (!moves.is_empty()).then(|| moves[rng.below(moves.len())].clone())
+}
+
+/// the same move, as it is played in `position`
+fn replayed(r#move: &Move, position: &Position) -> Option<Move> {
+    position
+        .legal_moves()
+        .into_iter()
+        .find(|m| m.object.props.oid == r#move.object.props.oid && m.target() == r#move.target())
+}
+
+/// the positions of some random games, and of the same moves played in another order
+fn positions(map: &str, seed: u64, rules: RuleSet) -> Vec<Position> {
+    let mut rng = Rng::new(seed);
+    let mut position = Position::new(&board(map), Player::A, 0, &rules);
+    let mut positions = vec![position.clone()];
+    for _ in 0..60 {
+        if position.winner().is_some() {
+            break;
+        }
+        // a; b; c and c; b; a often come to the same
+        let (a, c) = (
+            random_move(&position, &mut rng),
+            random_move(&position, &mut rng),
+        );
+        let after_a = position.play(a.as_ref());
+        let b = random_move(&after_a, &mut rng);
+        let after_c = position.play(c.as_ref());
+        let b_again = b.as_ref().and_then(|b| replayed(b, &after_c));
+        let a_again = a
+            .as_ref()
+            .and_then(|a| replayed(a, &after_c.play(b_again.as_ref())));
+        let after_ab = after_a.play(b.as_ref());
+        let c_again = c.as_ref().and_then(|c| replayed(c, &after_ab));
+        if let (Some(_), Some(_), Some(_)) = (&a_again, &b_again, &c_again) {
+            positions.push(after_ab.play(c_again.as_ref()));
+            positions.push(after_c.play(b_again.as_ref()).play(a_again.as_ref()));
+        }
+        position = position.play(random_move(&position, &mut rng).as_ref());
+        positions.push(position.clone());
+    }
+    positions
+}
+
+fn assert_equal_iff_same(positions: &[Position]) -> usize {
+    let mut by_hash: HashMap<u64, Vec<String>> = HashMap::new();
+    let mut by_position: HashMap<Vec<String>, u64> = HashMap::new();
+    let mut repeats = 0;
+    for position in positions {
+        let (hash, features) = (position.hash(), describe(position));
+        if let Some(seen) = by_hash.insert(hash, features.clone()) {
+            assert_eq!(seen, features, "different positions with the same hash");
+            repeats += 1;
+        }
+        if let Some(seen) = by_position.insert(features, hash) {
+            assert_eq!(seen, hash, "the same position with different hashes");
+        }
+    }
+    repeats
+}
+
+#[test]
+fn hashes_are_equal_iff_positions_are() {
+    let mut repeats = 0;
+    for map in MAPS {
+        for seed in 0..4 {
+            repeats += assert_equal_iff_same(&positions(map, seed, RuleSet::default()));
+        }
+    }
+    // the test means nothing unless some positions came up more than once
+    assert!(repeats > 0);
+}
+
+#[test]
+fn without_falling_tiles_positions_repeat_on_any_move() {
+    let rules = RuleSet {
+        falling_tiles: false,
+        ..RuleSet::default()
+    };
+    let mut repeats = 0;
+    for map in MAPS {
+        let positions = positions(map, 7, rules);
+        repeats += assert_equal_iff_same(&positions);
+        let at = |move_nr| Position::new(&board(map), Player::A, move_nr, &rules);
+        assert_eq!(at(0).hash(), at(2).hash());
+    }
+    assert!(repeats > 0);
+}
+
+#[test]
+fn hashes_tell_apart_who_is_to_move_and_when_tiles_fall() {
+    let rules = RuleSet::default();
+    let board = Board::test_square();
+    let hash = |to_move, move_nr| Position::new(&board, to_move, move_nr, &rules).hash();
+    assert_ne!(hash(Player::A, 0), hash(Player::B, 0));
+    assert_ne!(hash(Player::A, 0), hash(Player::A, 2));
+}
+
+#[test]
+fn hashes_are_kept_up_to_date() {
+    // the controller changes its board in place, with animations and all;
+    // its hash must come out as if the position had been built from scratch
+    let rules = RuleSet::default();
+    for map in MAPS {
+        let mut rng = Rng::new(3);
+        let mut clock = ManualClock::new(0.0);
+        let mut game = GameController::with_board(board(map));
+        game.start_game(&clock);
+        clock.advance(10.0);
+        game.tick(&clock);
+        for move_nr in 0..40 {
+            if game.game_state.outcome().is_some() {
+                break;
+            }
+            let to_move = game.current_player();
+            let position = Position::new(&game.board, to_move, move_nr, &rules);
+            assert_eq!(game.hash(), position.hash());
+            match random_move(&position, &mut rng) {
+                Some(r#move) => game.apply_move(&r#move, &clock, 0.5),
+                None => break,
+            }
+            clock.advance(0.2);
+            game.tick(&clock);
+        }
+    }
+}